@@ -1,71 +1,13 @@
 use std::hint::black_box;
 use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
-use erato::PrimalityRegistry;
-
-/// Generate test numbers for small range
-fn generate_small_primes() -> Vec<u64> {
-    vec![
-        97, 101, 103, 107, 109, 113, 127, 131, 137, 139,
-        149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
-    ]
-}
-
-/// Generate test numbers for medium range
-fn generate_medium_primes() -> Vec<u64> {
-    vec![
-        10007, 10009, 10037, 10039, 10061, 10067, 10069, 10079, 10091, 10093,
-        100003, 100019, 100043, 100049, 100057, 100069, 100103, 100109, 100129, 100151,
-    ]
-}
-
-/// Generate test numbers for large range
-fn generate_large_primes() -> Vec<u64> {
-    vec![
-        1_000_003, 1_000_033, 1_000_037, 1_000_039, 1_000_081,
-        10_000_019, 10_000_079, 10_000_139, 10_000_169, 10_000_189,
-        100_000_007, 100_000_037, 100_000_039, 100_000_049, 100_000_061,
-        1_000_000_007, 1_000_000_009, 1_000_000_021, 1_000_000_033, 1_000_000_087,
-    ]
-}
-
-/// Generate test numbers for very large range
-fn generate_very_large_primes() -> Vec<u64> {
-    vec![
-        10_000_000_019, 10_000_000_033, 10_000_000_061, 10_000_000_069, 10_000_000_097,
-        100_000_000_003, 100_000_000_019, 100_000_000_037, 100_000_000_039, 100_000_000_049,
-    ]
-}
-
-/// Generate challenging composites (products of large primes)
-fn generate_very_large_composites() -> Vec<u64> {
-    vec![
-        // Semiprimes (products of 2 large primes) - hardest case for trial division
-        9_999_997_000_029_991,  // 9999991 * 1000003
-        10_000_056_000_703,     // 10000019 * 1000037
-        10_000_001_400_000_063, // 100000007 * 100000009
-    ]
-}
-
-/// Generate composite numbers for correctness testing
-fn generate_small_composites() -> Vec<u64> {
-    vec![
-        4, 6, 8, 9, 10, 12, 14, 15, 16, 18,
-        20, 21, 22, 24, 25, 26, 27, 28, 30, 32,
-    ]
-}
-
-/// Generate larger composite numbers
-fn generate_medium_composites() -> Vec<u64> {
-    vec![
-        1000, 1001, 1002, 10000, 10001, 10002, 100000, 100001, 100002,
-        1000000, 1000001, 1000002, 10000000, 10000001, 10000002,
-    ]
-}
+use erato::{test_vectors, MillerRabinAlgorithm, PrimalityRegistry, PrimalityTest, SieveAlgorithm, SievePolicy, DETERMINISTIC_WITNESSES};
+use erato::{is_prime_miller_rabin, is_prime_sieve, primes_up_to, probable_prime_report};
+use erato::{ModArith, Montgomery, LARGEST_U64_PRIME};
 
 /// Benchmark small primes (< 200)
 fn bench_small_primes(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_small_primes();
+    let test_numbers = test_vectors().small_primes;
 
     let mut group = c.benchmark_group("small_primes");
     group.sample_size(1000);
@@ -74,7 +16,7 @@ fn bench_small_primes(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -87,10 +29,10 @@ fn bench_small_primes(c: &mut Criterion) {
     group.finish();
 }
 
-/// Benchmark small composites (< 50)
+/// Benchmark small composites (< 200)
 fn bench_small_composites(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_small_composites();
+    let test_numbers = test_vectors().small_composites;
 
     let mut group = c.benchmark_group("small_composites");
     group.sample_size(1000);
@@ -99,7 +41,7 @@ fn bench_small_composites(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -112,10 +54,10 @@ fn bench_small_composites(c: &mut Criterion) {
     group.finish();
 }
 
-/// Benchmark medium primes (10^3 to 10^5)
+/// Benchmark medium primes (10^4 to 10^6)
 fn bench_medium_primes(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_medium_primes();
+    let test_numbers = test_vectors().medium_primes;
 
     let mut group = c.benchmark_group("medium_primes");
     group.sample_size(500);
@@ -124,7 +66,7 @@ fn bench_medium_primes(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -140,7 +82,7 @@ fn bench_medium_primes(c: &mut Criterion) {
 /// Benchmark medium composites (10^3 to 10^7)
 fn bench_medium_composites(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_medium_composites();
+    let test_numbers = test_vectors().medium_composites;
 
     let mut group = c.benchmark_group("medium_composites");
     group.sample_size(200);
@@ -149,7 +91,7 @@ fn bench_medium_composites(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -162,10 +104,10 @@ fn bench_medium_composites(c: &mut Criterion) {
     group.finish();
 }
 
-/// Benchmark large primes (10^6 to 10^9)
+/// Benchmark large primes (10^3 to 10^10)
 fn bench_large_primes(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_large_primes();
+    let test_numbers = test_vectors().large_primes;
 
     let mut group = c.benchmark_group("large_primes");
     group.sample_size(100);
@@ -174,7 +116,7 @@ fn bench_large_primes(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -187,10 +129,10 @@ fn bench_large_primes(c: &mut Criterion) {
     group.finish();
 }
 
-/// Benchmark very large primes (10^9 to 10^11)
+/// Benchmark very large primes (10^10 to 10^13)
 fn bench_very_large_primes(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_very_large_primes();
+    let test_numbers = test_vectors().very_large_primes;
 
     let mut group = c.benchmark_group("very_large_primes");
     group.sample_size(50);
@@ -199,7 +141,7 @@ fn bench_very_large_primes(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -215,7 +157,7 @@ fn bench_very_large_primes(c: &mut Criterion) {
 /// Benchmark very large composites (semiprimes - hardest case for trial division)
 fn bench_very_large_composites(c: &mut Criterion) {
     let registry = PrimalityRegistry::<u64>::with_all_algorithms();
-    let test_numbers = generate_very_large_composites();
+    let test_numbers = test_vectors().very_large_composites;
 
     let mut group = c.benchmark_group("very_large_composites");
     group.sample_size(30);
@@ -224,7 +166,7 @@ fn bench_very_large_composites(c: &mut Criterion) {
     for algo in registry.algorithms() {
         group.bench_with_input(
             BenchmarkId::from_parameter(algo.name()),
-            &test_numbers,
+            test_numbers,
             |b, numbers| {
                 b.iter(|| {
                     for &n in numbers {
@@ -297,6 +239,186 @@ fn bench_edge_cases(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark the 12-base deterministic witnesses against the 7-base set
+fn bench_witness_sets(c: &mut Criterion) {
+    let test_numbers = test_vectors().very_large_primes;
+    let witness_sets = [
+        ("12 prime bases", MillerRabinAlgorithm::with_witnesses(DETERMINISTIC_WITNESSES.to_vec())),
+        ("7 Sorenson-Webster bases", MillerRabinAlgorithm::sorenson_webster()),
+    ];
+
+    let mut group = c.benchmark_group("miller_rabin_witnesses");
+
+    for (label, algo) in &witness_sets {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(label),
+            test_numbers,
+            |b, numbers| {
+                b.iter(|| {
+                    for &n in numbers {
+                        black_box(PrimalityTest::<u64>::is_prime(algo, black_box(n)));
+                    }
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Benchmark the Fermat base-2 pre-filter on the composite groups
+///
+/// Shows what the single exponentiation saves, or costs, before the witness
+/// loop on inputs that are mostly rejected.
+fn bench_fermat_prefilter(c: &mut Criterion) {
+    let vectors = test_vectors();
+    let groups = [
+        ("small_composites", vectors.small_composites),
+        ("medium_composites", vectors.medium_composites),
+        ("very_large_composites", vectors.very_large_composites),
+        ("carmichael", vectors.carmichael),
+    ];
+    let variants = [
+        ("plain", MillerRabinAlgorithm::default()),
+        ("fermat_prefilter", MillerRabinAlgorithm::default().with_fermat_prefilter(true)),
+    ];
+
+    let mut group = c.benchmark_group("fermat_prefilter");
+    for (group_name, numbers) in groups {
+        for (label, algo) in &variants {
+            group.bench_with_input(BenchmarkId::new(*label, group_name), numbers, |b, numbers| {
+                b.iter(|| {
+                    for &n in numbers {
+                        black_box(PrimalityTest::<u64>::is_prime(algo, black_box(n)));
+                    }
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Benchmark pow_mod with `%`-based mul_mod against Montgomery multiplication
+///
+/// Times the exponentiation 2^(m-1) mod m at the heart of Miller-Rabin on its
+/// own, so the arithmetic speedup is not diluted by the rest of the test.
+fn bench_montgomery(c: &mut Criterion) {
+    let moduli: Vec<u64> = test_vectors()
+        .very_large_primes
+        .iter()
+        .copied()
+        .chain([LARGEST_U64_PRIME, (1 << 61) - 1, 1_000_000_000_000_000_003])
+        .collect();
+    let contexts: Vec<Montgomery> = moduli.iter().map(|&m| Montgomery::new(m).unwrap()).collect();
+
+    let mut group = c.benchmark_group("pow_mod");
+    group.bench_function("naive", |b| {
+        b.iter(|| {
+            for &m in &moduli {
+                black_box(black_box(2u64).pow_mod(m - 1, m));
+            }
+        });
+    });
+    group.bench_function("montgomery", |b| {
+        b.iter(|| {
+            for montgomery in &contexts {
+                black_box(montgomery.pow_mod(black_box(2), montgomery.modulus() - 1));
+            }
+        });
+    });
+    group.finish();
+}
+
+/// Benchmark repeated small queries under both sieve policies
+///
+/// The bitset policy answers with a lookup where trial division performs
+/// about √n / 2 divisions per query, so it should come out well ahead.
+fn bench_sieve_policies(c: &mut Criterion) {
+    let limit = 200_000u64;
+    let policies = [
+        ("trial_division", SieveAlgorithm::with_policy(SievePolicy::TrialDivision)),
+        ("bitset", SieveAlgorithm::with_policy(SievePolicy::Bitset { limit })),
+    ];
+
+    let mut group = c.benchmark_group("sieve_policies");
+    for (label, sieve) in &policies {
+        group.bench_function(*label, |b| {
+            b.iter(|| {
+                (limit / 2..=limit)
+                    .filter(|&n| PrimalityTest::<u64>::is_prime(sieve, black_box(n)))
+                    .count()
+            });
+        });
+    }
+    group.finish();
+}
+
+/// A named standalone primality function
+type Implementation = (&'static str, fn(u64) -> bool);
+
+/// Standalone primality functions compared by `bench_implementations`
+///
+/// Each entry is a plain `fn(u64) -> bool`, so implementations from other
+/// crates are compared on exactly the same inputs. With the `compare_primal`
+/// feature, `primal::is_prime` is included as an external reference.
+fn implementations() -> Vec<Implementation> {
+    vec![
+        ("miller_rabin", |n| is_prime_miller_rabin(n, 0)),
+        ("trial_division", is_prime_sieve),
+        ("baillie_psw", |n| probable_prime_report(n).verdict),
+        #[cfg(feature = "compare_primal")]
+        ("primal", primal::is_prime),
+    ]
+}
+
+/// Benchmark the standalone primality functions on the shared test vectors
+///
+/// Before timing anything, checks that every implementation agrees with the
+/// first one on every input, so a fast but wrong implementation is caught.
+fn bench_implementations(c: &mut Criterion) {
+    let vectors = test_vectors();
+    let inputs: Vec<u64> = vectors
+        .small_primes
+        .iter()
+        .chain(vectors.medium_primes)
+        .chain(vectors.large_primes)
+        .chain(vectors.medium_composites)
+        .chain(vectors.carmichael)
+        .copied()
+        .collect();
+
+    let implementations = implementations();
+    let (reference_name, reference) = implementations[0];
+    for &(name, is_prime) in &implementations[1..] {
+        for &n in &inputs {
+            assert_eq!(is_prime(n), reference(n), "{} disagrees with {} on {}", name, reference_name, n);
+        }
+    }
+
+    let mut group = c.benchmark_group("implementations");
+    for (name, is_prime) in implementations {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &inputs, |b, numbers| {
+            b.iter(|| {
+                for &n in numbers {
+                    black_box(is_prime(black_box(n)));
+                }
+            });
+        });
+    }
+
+    let limit = 1_000_000u64;
+    group.bench_function("primes_up_to", |b| {
+        b.iter(|| black_box(primes_up_to(black_box(limit))));
+    });
+    #[cfg(feature = "compare_primal")]
+    group.bench_function("primal_sieve", |b| {
+        b.iter(|| {
+            let sieve = primal::Sieve::new(black_box(limit as usize));
+            black_box(sieve.primes_from(0).collect::<Vec<_>>())
+        });
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_small_primes,
@@ -309,6 +431,50 @@ criterion_group!(
     bench_single_prime,
     bench_single_composite,
     bench_edge_cases,
+    bench_witness_sets,
+    bench_fermat_prefilter,
+    bench_montgomery,
+    bench_sieve_policies,
+    bench_implementations,
 );
 
+/// Benchmark the parallel segmented sieve against the serial sieve
+///
+/// Runs the parallel sieve on thread pools of increasing size to show
+/// how prime generation scales with the number of cores.
+#[cfg(feature = "parallel")]
+fn bench_parallel_sieve(c: &mut Criterion) {
+    let limit = 10_000_000u64;
+
+    let mut group = c.benchmark_group("parallel_sieve");
+    group.sample_size(10);
+
+    group.bench_function("serial", |b| {
+        b.iter(|| black_box(erato::primes_up_to(black_box(limit))));
+    });
+
+    for threads in [1, 2, 4, 8] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("parallel", threads),
+            &limit,
+            |b, &limit| {
+                b.iter(|| pool.install(|| black_box(erato::primes_up_to_parallel(black_box(limit)))));
+            },
+        );
+    }
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+criterion_group!(parallel_benches, bench_parallel_sieve);
+
+#[cfg(feature = "parallel")]
+criterion_main!(benches, parallel_benches);
+
+#[cfg(not(feature = "parallel"))]
 criterion_main!(benches);
@@ -0,0 +1,17 @@
+use erato::{primes_up_to, PrimeIterator};
+
+fn main() {
+    let limit = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse::<u64>().expect("limit must be a non-negative integer"))
+        .unwrap_or(100);
+
+    let primes = primes_up_to(limit);
+    let line: Vec<String> = primes.iter().map(|p| p.to_string()).collect();
+    println!("Primes up to {}: {}", limit, line.join(", "));
+    println!("Count: {}", primes.len());
+
+    // The streaming iterator produces the same sequence without a fixed bound
+    let streamed = PrimeIterator::new().take_while(|&p| p <= limit).count();
+    println!("PrimeIterator agrees: {}", streamed == primes.len());
+}
@@ -7,6 +7,11 @@ fn main() {
     println!("100 is prime: {}", zeta.is_prime(100u64));
     println!("1000000007 is prime: {}", zeta.is_prime(1_000_000_007u64));
     
+    // Trial division up to √n would take far too long for the largest u64
+    // prime, so the zeta test refuses it instead of hanging
     let largest_prime_u64 = 18_446_744_073_709_551_557u64;
-    println!("18,446,744,073,709,551,557 is prime: {}", zeta.is_prime(largest_prime_u64));
+    match zeta.try_is_prime(largest_prime_u64) {
+        Ok(result) => println!("18,446,744,073,709,551,557 is prime: {}", result),
+        Err(err) => println!("18,446,744,073,709,551,557 is out of range: {}", err),
+    }
 }
@@ -0,0 +1,476 @@
+use num_traits::{PrimInt, ToPrimitive, FromPrimitive, Unsigned};
+
+/// Overflow-safe modular arithmetic over the generic integer types
+///
+/// Every algorithm that works modulo n (Miller-Rabin and friends) needs the same
+/// handful of operations, and each of them must avoid overflowing `N` for
+/// operands close to the modulus. This trait provides them once for all
+/// unsigned primitive integers through a blanket implementation.
+///
+/// # Overflow handling
+///
+/// - Types up to 64 bits multiply through a u128 intermediate
+/// - Wider types (u128) use double-and-add, which never exceeds the modulus
+///
+/// All operands are expected to be already reduced modulo `m`, except for
+/// `mul_mod` and `pow_mod` which reduce their inputs themselves. With the
+/// `overflow_safe` feature enabled this precondition is checked and violations
+/// panic with a descriptive message instead of silently producing a wrong result.
+pub trait ModArith: PrimInt + ToPrimitive + FromPrimitive + Unsigned {
+    /// Computes (self + b) mod m without overflow
+    fn add_mod(self, b: Self, m: Self) -> Self {
+        check_reduced(self, b, m, "add_mod");
+
+        if self >= m - b {
+            self - (m - b)
+        } else {
+            self + b
+        }
+    }
+
+    /// Computes (self - b) mod m without underflow
+    fn sub_mod(self, b: Self, m: Self) -> Self {
+        check_reduced(self, b, m, "sub_mod");
+
+        if self >= b {
+            self - b
+        } else {
+            m - (b - self)
+        }
+    }
+
+    /// Computes (self × b) mod m without overflow
+    fn mul_mod(self, b: Self, m: Self) -> Self {
+        if Self::zero().count_zeros() <= 64 {
+            let a_u128 = self.to_u128().unwrap();
+            let b_u128 = b.to_u128().unwrap();
+            let m_u128 = m.to_u128().unwrap();
+
+            // Both operands are below 2^64, so the product always fits in a u128
+            return Self::from_u128((a_u128 * b_u128) % m_u128).unwrap();
+        }
+
+        // No wider primitive available: double-and-add, one bit of b at a time
+        let one = Self::one();
+        let mut a = self % m;
+        let mut b = b % m;
+        let mut result = Self::zero();
+
+        while b > Self::zero() {
+            if b & one == one {
+                result = result.add_mod(a, m);
+            }
+            a = a.add_mod(a, m);
+            b = b >> 1;
+        }
+
+        result
+    }
+
+    /// Computes self^exp mod m using binary exponentiation
+    fn pow_mod(self, mut exp: Self, m: Self) -> Self {
+        let zero = Self::zero();
+        let one = Self::one();
+
+        let mut base = self % m;
+        let mut result = one % m;
+
+        while exp > zero {
+            if exp & one == one {
+                result = result.mul_mod(base, m);
+            }
+            exp = exp >> 1;
+            base = base.mul_mod(base, m);
+        }
+
+        result
+    }
+}
+
+impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> ModArith for N {}
+
+/// Inverse of an odd p modulo 2^64, by Newton iteration
+///
+/// p is its own inverse modulo 2^3, and each step doubles the number of
+/// correct low bits, so five steps reach 96 > 64 bits.
+pub(crate) const fn inverse_mod_2_64(p: u64) -> u64 {
+    let mut inverse = p;
+    let mut step = 0;
+    while step < 5 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inverse)));
+        step += 1;
+    }
+    inverse
+}
+
+/// Montgomery arithmetic modulo a fixed odd u64
+///
+/// An alternative to `ModArith::mul_mod` for repeated products modulo the
+/// same m: values are held in Montgomery form a × 2^64 mod m, where a
+/// product is reduced with two multiplications and a shift instead of a
+/// 128-bit division. Converting in and out costs one product each, so it
+/// pays off over a whole exponentiation rather than a single product.
+///
+/// # References
+///
+/// P. L. Montgomery, "Modular multiplication without trial division",
+/// Mathematics of Computation 44 (1985)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery {
+    /// The modulus
+    m: u64,
+    /// m⁻¹ mod 2^64
+    inverse: u64,
+    /// 2^64 mod m, the Montgomery form of 1
+    r: u64,
+    /// 2^128 mod m, which converts into Montgomery form
+    r2: u64,
+}
+
+impl Montgomery {
+    /// Prepares arithmetic modulo m
+    ///
+    /// # Returns
+    ///
+    /// `None` if m is even, as Montgomery reduction needs m coprime to 2^64
+    pub fn new(m: u64) -> Option<Self> {
+        if m.is_multiple_of(2) {
+            return None;
+        }
+        let r = ((1u128 << 64) % m as u128) as u64;
+        let r2 = (r as u128 * r as u128 % m as u128) as u64;
+        Some(Montgomery { m, inverse: inverse_mod_2_64(m), r, r2 })
+    }
+
+    /// Returns the modulus
+    pub fn modulus(&self) -> u64 {
+        self.m
+    }
+
+    /// Converts a into Montgomery form, a × 2^64 mod m
+    pub fn to_montgomery(&self, a: u64) -> u64 {
+        self.mul(a % self.m, self.r2)
+    }
+
+    /// Converts a Montgomery form back to the ordinary residue
+    pub fn from_montgomery(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    /// Multiplies two values in Montgomery form
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    /// Computes base^exp mod m by binary exponentiation in Montgomery form
+    ///
+    /// Takes and returns ordinary residues, so it can stand in for
+    /// `ModArith::pow_mod` on u64.
+    pub fn pow_mod(&self, base: u64, mut exp: u64) -> u64 {
+        let mut base = self.to_montgomery(base);
+        let mut result = self.r;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            exp >>= 1;
+            base = self.mul(base, base);
+        }
+
+        self.from_montgomery(result)
+    }
+
+    /// Montgomery reduction: t × 2^-64 mod m, for t < m × 2^64
+    fn reduce(&self, t: u128) -> u64 {
+        // q × m agrees with t on the low 64 bits, so t - q × m is a multiple
+        // of 2^64 with |t - q × m| < m × 2^64
+        let q = (t as u64).wrapping_mul(self.inverse);
+        let qm_high = ((q as u128 * self.m as u128) >> 64) as u64;
+        let t_high = (t >> 64) as u64;
+        if t_high >= qm_high {
+            t_high - qm_high
+        } else {
+            t_high.wrapping_sub(qm_high).wrapping_add(self.m)
+        }
+    }
+}
+
+/// Jacobi symbol (a / n) for odd positive n
+///
+/// Generalizes the Legendre symbol: for prime n it is 1 if a is a non-zero
+/// quadratic residue modulo n, -1 if it is a non-residue and 0 if n divides a.
+///
+/// # Arguments
+///
+/// * `a` - The "numerator", any value
+/// * `n` - The "denominator", must be odd
+///
+/// # Returns
+///
+/// -1, 0 or 1
+pub fn jacobi<N: ModArith>(a: N, n: N) -> i8 {
+    let zero = N::zero();
+    let one = N::one();
+    let three = N::from_u64(3).unwrap();
+    let four = N::from_u64(4).unwrap();
+    let five = N::from_u64(5).unwrap();
+    let eight = N::from_u64(8).unwrap();
+
+    let mut a = a % n;
+    let mut n = n;
+    let mut result = 1i8;
+
+    while a != zero {
+        // Pull out factors of two: (2 / n) = -1 iff n ≡ 3, 5 (mod 8)
+        while a & one == zero {
+            a = a >> 1;
+            let r = n % eight;
+            if r == three || r == five {
+                result = -result;
+            }
+        }
+
+        // Quadratic reciprocity: flip the sign if both are ≡ 3 (mod 4)
+        std::mem::swap(&mut a, &mut n);
+        if a % four == three && n % four == three {
+            result = -result;
+        }
+        a = a % n;
+    }
+
+    if n == one { result } else { 0 }
+}
+
+/// Square root of a modulo an odd prime p, by the Tonelli-Shanks algorithm
+///
+/// # Arguments
+///
+/// * `a` - The value whose root is wanted, any value (reduced modulo p)
+/// * `p` - The modulus, which must be an odd prime (or 2)
+///
+/// # Returns
+///
+/// `Some(r)` with r² ≡ a (mod p), the smaller of the two roots r and p - r,
+/// or `None` if a is a quadratic non-residue. `None` is also returned without
+/// searching when p is 0, even and not 2, or a perfect square, none of which
+/// has the quadratic non-residue the algorithm needs. For any other composite
+/// p the result is meaningless.
+///
+/// # References
+///
+/// See [Tonelli-Shanks algorithm](https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm)
+pub fn mod_sqrt(a: u64, p: u64) -> Option<u64> {
+    if p == 0 || (p.is_multiple_of(2) && p != 2) {
+        return None;
+    }
+    // The Jacobi symbol (z / p) is never -1 for a square p, so the search for
+    // z below would not terminate
+    let root = p.isqrt();
+    if root * root == p {
+        return None;
+    }
+
+    let a = a % p;
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    if jacobi(a, p) != 1 {
+        return None;
+    }
+
+    // p - 1 = q × 2^s with q odd
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+
+    // Any quadratic non-residue z works; the search is short in practice
+    let mut z = 2u64;
+    while jacobi(z, p) != -1 {
+        z += 1;
+    }
+
+    let mut m = s;
+    let mut c = z.pow_mod(q, p);
+    let mut t = a.pow_mod(q, p);
+    let mut r = a.pow_mod(q.div_ceil(2), p);
+    while t != 1 {
+        // Least i with t^(2^i) = 1; i reaching m means p was not prime
+        let mut i = 0;
+        let mut t_pow = t;
+        while t_pow != 1 {
+            t_pow = t_pow.mul_mod(t_pow, p);
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+
+        let mut b = c;
+        for _ in 0..m - i - 1 {
+            b = b.mul_mod(b, p);
+        }
+        m = i;
+        c = b.mul_mod(b, p);
+        t = t.mul_mod(c, p);
+        r = r.mul_mod(b, p);
+    }
+
+    Some(r.min(p - r))
+}
+
+/// Computes a × p + b, or `None` if the result overflows a u64
+///
+/// Prime-pattern searches ask whether p and some f(p) = a × p + b are both
+/// prime (Sophie Germain primes, safe primes, Cunningham chains). Near
+/// u64::MAX the image silently wraps with plain arithmetic, so every such
+/// helper goes through this function instead.
+///
+/// # Arguments
+///
+/// * `p` - The point at which the map is evaluated
+/// * `a` - The multiplier
+/// * `b` - The offset
+///
+/// # Returns
+///
+/// `Some(a × p + b)`, or `None` on overflow
+pub fn checked_affine(p: u64, a: u64, b: u64) -> Option<u64> {
+    a.checked_mul(p)?.checked_add(b)
+}
+
+/// Adds two values, checking for overflow when `overflow_safe` is enabled
+///
+/// Without the feature this compiles down to a plain addition.
+#[inline]
+pub(crate) fn overflow_add<N: PrimInt>(a: N, b: N, context: &str) -> N {
+    #[cfg(feature = "overflow_safe")]
+    {
+        a.checked_add(&b).unwrap_or_else(|| {
+            panic!("overflow in {}: addition exceeds {}", context, std::any::type_name::<N>())
+        })
+    }
+    #[cfg(not(feature = "overflow_safe"))]
+    {
+        let _ = context;
+        a + b
+    }
+}
+
+/// Converts a u64 into `N`, saturating at `N::max_value()` if it does not fit
+///
+/// Loop bounds such as ⌊√n⌋ + 1 are computed in u64 and converted back to the
+/// generic type; for narrow types (u8, u16) the plain `from_u64(..).unwrap()`
+/// would panic whenever the bound exceeds the type's range.
+#[inline]
+pub(crate) fn saturating_from_u64<N: PrimInt + FromPrimitive>(value: u64) -> N {
+    N::from_u64(value).unwrap_or_else(N::max_value)
+}
+
+/// Integer square root ⌊√n⌋ for any primitive integer type
+///
+/// Newton's iteration x ← (x + n / x) / 2, started from a power of two above
+/// √n, decreases monotonically to ⌊√n⌋ using only `N` arithmetic. Unlike a
+/// square root through f64, which rounds inputs above 2^53, the result is
+/// exact for every width up to u128.
+///
+/// # Returns
+///
+/// The largest r with r² ≤ n
+pub fn integer_sqrt<N: PrimInt>(n: N) -> N {
+    let one = N::one();
+    let two = one + one;
+    if n < two {
+        return n;
+    }
+
+    // 2^⌈b/2⌉ > √n for an n of b significant bits
+    let bits = (N::zero().count_zeros() - n.leading_zeros()) as usize;
+    let mut x = one << bits.div_ceil(2);
+    loop {
+        let next = (x + n / x) / two;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// Integer k-th root ⌊n^(1/k)⌋ of a u64, for k ≥ 1
+///
+/// Starts from the f64 root and corrects the rounding with exact powers.
+fn integer_root(n: u64, k: u32) -> u64 {
+    let fits = |r: u64| r.checked_pow(k).is_some_and(|power| power <= n);
+    let mut root = (n as f64).powf(1.0 / k as f64) as u64;
+    while !fits(root) {
+        root -= 1;
+    }
+    while fits(root + 1) {
+        root += 1;
+    }
+    root
+}
+
+/// Writes n as a perfect power b^k with k ≥ 2, if it is one
+///
+/// Every exponent up to log2(n) is tried with an exact integer root and the
+/// largest one that works is kept, so b is as small as possible and is not a
+/// perfect power itself: 64 gives (2, 6) rather than (8, 2).
+///
+/// # Returns
+///
+/// `Some((b, k))` with the largest k ≥ 2 such that n = b^k, or `None` if n is
+/// not a perfect power. 0 and 1, powers of themselves to every exponent, give
+/// `None`.
+pub fn is_perfect_power(n: u64) -> Option<(u64, u32)> {
+    if n < 4 {
+        return None;
+    }
+    (2..=n.ilog2())
+        .rev()
+        .map(|k| (integer_root(n, k), k))
+        .find(|&(root, k)| root.pow(k) == n)
+}
+
+/// Number of significant bits of n
+///
+/// The k of a "k-bit number": n lies in [2^(k-1), 2^k).
+///
+/// # Returns
+///
+/// ⌊log2 n⌋ + 1, or 0 when n is 0
+pub fn bit_length(n: u64) -> u32 {
+    u64::BITS - n.leading_zeros()
+}
+
+/// Number of digits of n written in decimal
+///
+/// # Returns
+///
+/// ⌊log10 n⌋ + 1, or 1 when n is 0, which is written with one digit
+pub fn decimal_digits(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// Upper bound for trial division: ⌊√n⌋ + 1
+///
+/// Computed with `integer_sqrt`, so the bound is exact even where a float
+/// square root would round below a factor; ⌊√n⌋ + 1 always fits in `N`.
+#[inline]
+pub(crate) fn trial_division_limit<N: PrimInt>(n: N) -> N {
+    integer_sqrt(n) + N::one()
+}
+
+/// Checks that both operands are reduced modulo m when `overflow_safe` is enabled
+#[inline]
+fn check_reduced<N: PrimInt>(a: N, b: N, m: N, context: &str) {
+    #[cfg(feature = "overflow_safe")]
+    {
+        if a >= m || b >= m {
+            panic!("overflow in {}: operands must be reduced modulo m", context);
+        }
+    }
+    #[cfg(not(feature = "overflow_safe"))]
+    {
+        let _ = (a, b, m, context);
+    }
+}
@@ -0,0 +1,199 @@
+use super::arith::{jacobi, ModArith};
+use super::factor::{gcd, has_small_factor};
+use super::miller_rabin::is_strong_probable_prime;
+
+/// Trial divisors used by `probable_prime_report` go up to this bound
+const TRIAL_DIVISION_BOUND: u64 = 1000;
+
+/// Outcome of each sub-test run by `probable_prime_report`
+///
+/// The three tests are independent: no composite is known that passes both
+/// the base-2 strong test and the strong Lucas test, and none exists below
+/// 2^64, so their conjunction (the Baillie-PSW test) is deterministic for u64.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrpReport {
+    /// Smallest prime factor below the trial division bound, if any
+    pub small_factor: Option<u64>,
+    /// Whether n is a strong probable prime to base 2
+    pub strong_base_2: bool,
+    /// Whether n is a strong Lucas probable prime with Selfridge's parameters
+    pub strong_lucas: bool,
+    /// Overall Baillie-PSW verdict: `true` if n is prime
+    pub verdict: bool,
+}
+
+/// Runs trial division, the base-2 strong test and the strong Lucas test on n
+///
+/// Gives a defense-in-depth view of a primality verdict: each sub-result is
+/// reported on its own, together with the combined Baillie-PSW verdict. Every
+/// sub-test is run even when an earlier one already proves n composite.
+///
+/// # Arguments
+///
+/// * `n` - The number to test
+///
+/// # Returns
+///
+/// A `PrpReport` with each sub-result and the overall verdict
+///
+/// # References
+///
+/// See [Baillie-PSW primality test](https://en.wikipedia.org/wiki/Baillie%E2%80%93PSW_primality_test)
+pub fn probable_prime_report(n: u64) -> PrpReport {
+    let small_factor = has_small_factor(n, TRIAL_DIVISION_BOUND);
+    let strong_base_2 = is_strong_probable_prime(n, 2);
+    let strong_lucas = is_strong_lucas_probable_prime(n);
+
+    // Base 2 is ≡ 0 mod 2, so the strong test alone would reject n = 2
+    let verdict = n == 2 || (small_factor.is_none() && strong_base_2 && strong_lucas);
+
+    PrpReport {
+        small_factor,
+        strong_base_2,
+        strong_lucas,
+        verdict,
+    }
+}
+
+/// Strong Lucas probable prime test with Selfridge's method A parameters
+///
+/// Picks the first D in 5, -7, 9, -11, ... with Jacobi symbol (D / n) = -1,
+/// sets P = 1 and Q = (1 - D) / 4, writes n + 1 = 2^s × d with d odd and checks
+/// that U_d ≡ 0 or V_(2^r × d) ≡ 0 (mod n) for some 0 ≤ r < s.
+///
+/// # Arguments
+///
+/// * `n` - The number to test
+///
+/// # Returns
+///
+/// `true` if n is a strong Lucas probable prime, `false` if n is composite
+/// (perfect squares, which admit no suitable D, are always rejected)
+pub fn is_strong_lucas_probable_prime(n: u64) -> bool {
+    if n < 3 || n.is_multiple_of(2) {
+        return n == 2;
+    }
+    let root = n.isqrt();
+    if root * root == n {
+        return false;
+    }
+
+    // Selfridge's method A: the search always ends since n is not a square
+    let mut d: i64 = 5;
+    loop {
+        let d_mod = reduce_signed(d, n);
+        match jacobi(d_mod, n) {
+            -1 => break,
+            // A common factor with |D| proves n composite, unless it is n itself
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -(d + 2) } else { -d + 2 },
+        }
+    }
+
+    // n is odd and not a square, so n + 1 cannot overflow
+    strong_lucas_check(n, 1, reduce_signed((1 - d) / 4, n), reduce_signed(d, n), n + 1)
+}
+
+/// Strong Lucas probable prime test with explicitly chosen parameters P and Q
+///
+/// The same test as `is_strong_lucas_probable_prime`, with the Lucas
+/// sequences U(P, Q) and V(P, Q) instead of Selfridge's choice. With
+/// D = P² - 4Q and ε the Jacobi symbol (D / n), it writes n - ε = 2^s × d with
+/// d odd and checks that U_d ≡ 0 or V_(2^r × d) ≡ 0 (mod n) for some
+/// 0 ≤ r < s; every prime n with gcd(n, QD) = 1 passes.
+///
+/// The parameters matter for composites: ε = -1 is what gives the test the
+/// strength used in Baillie-PSW, while with ε = 1 it is a much weaker check
+/// that many more composites pass. For parameters with gcd(n, QD) ≠ 1, which
+/// includes D = 0, the test does not apply and `false` is returned even when
+/// n is prime.
+///
+/// # Arguments
+///
+/// * `n` - The number to test
+/// * `p` - The Lucas parameter P
+/// * `q` - The Lucas parameter Q
+///
+/// # Returns
+///
+/// `true` if n is a strong Lucas probable prime for (P, Q), `false` if it is
+/// composite, even, or the parameters are unusable for n
+pub fn is_lucas_prp_with_params(n: u64, p: i64, q: i64) -> bool {
+    if n < 3 || n.is_multiple_of(2) {
+        return n == 2;
+    }
+
+    let d = p as i128 * p as i128 - 4 * q as i128;
+    let d_mod = d.rem_euclid(n as i128) as u64;
+    let q_mod = reduce_signed(q, n);
+    if gcd(q_mod, n) != 1 || gcd(d_mod, n) != 1 {
+        return false;
+    }
+
+    let index = match jacobi(d_mod, n) {
+        -1 => match n.checked_add(1) {
+            Some(index) => index,
+            // u64::MAX = 3 × 5 × 17 × ... is composite
+            None => return false,
+        },
+        _ => n - 1,
+    };
+    strong_lucas_check(n, reduce_signed(p, n), q_mod, d_mod, index)
+}
+
+/// Checks the strong Lucas conditions for U(P, Q) and V(P, Q) at index n - ε
+///
+/// The parameters are residues modulo the odd n, and `index` must be even.
+fn strong_lucas_check(n: u64, p: u64, q: u64, d_mod: u64, index: u64) -> bool {
+    let mut k = index;
+    let mut s = 0;
+    while k.is_multiple_of(2) {
+        k /= 2;
+        s += 1;
+    }
+
+    // Left-to-right binary ladder computing U_k, V_k and Q^k
+    let mut u = 1u64;
+    let mut v = p;
+    let mut q_k = q;
+    for bit in (0..k.ilog2()).rev() {
+        u = u.mul_mod(v, n);
+        v = v.mul_mod(v, n).sub_mod(q_k.add_mod(q_k, n), n);
+        q_k = q_k.mul_mod(q_k, n);
+
+        if (k >> bit) & 1 == 1 {
+            let next_u = half_mod(u.mul_mod(p, n).add_mod(v, n), n);
+            v = half_mod(d_mod.mul_mod(u, n).add_mod(v.mul_mod(p, n), n), n);
+            u = next_u;
+            q_k = q_k.mul_mod(q, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+    for _ in 1..s {
+        v = v.mul_mod(v, n).sub_mod(q_k.add_mod(q_k, n), n);
+        q_k = q_k.mul_mod(q_k, n);
+        if v == 0 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Maps a signed value to its residue in [0, n)
+fn reduce_signed(value: i64, n: u64) -> u64 {
+    let residue = value.unsigned_abs() % n;
+    if value < 0 && residue != 0 { n - residue } else { residue }
+}
+
+/// Divides x by 2 modulo the odd modulus n
+fn half_mod(x: u64, n: u64) -> u64 {
+    if x.is_multiple_of(2) {
+        x / 2
+    } else {
+        ((x as u128 + n as u128) / 2) as u64
+    }
+}
@@ -0,0 +1,157 @@
+use super::factor::smallest_factor_among;
+use super::sieve::primes_up_to;
+use super::{trivial_primality, AlgorithmInfo, Assessment, Certainty, Complexity, PrimalityTest};
+
+/// Fallback chain of primality tests
+///
+/// Consults its algorithms in order through `PrimalityTest::assess` and stops
+/// at the first one whose verdict is `Certainty::Proven`. Later algorithms only
+/// run when every earlier one was unsure, which makes "fast screen, then
+/// confirm" pipelines possible, e.g. a `TrialDivisionScreen` followed by
+/// Miller-Rabin.
+pub struct Chain {
+    algos: Vec<Box<dyn PrimalityTest<u64>>>,
+}
+
+impl Chain {
+    /// Creates a chain consulting the given algorithms in order
+    pub fn new(algos: Vec<Box<dyn PrimalityTest<u64>>>) -> Self {
+        Chain { algos }
+    }
+
+    /// Appends an algorithm to the end of the chain
+    ///
+    /// # Arguments
+    ///
+    /// * `algo` - The algorithm consulted when all the previous ones are unsure
+    pub fn then<T: PrimalityTest<u64> + 'static>(mut self, algo: T) -> Self {
+        self.algos.push(Box::new(algo));
+        self
+    }
+
+    /// Returns the algorithms of the chain, in the order they are consulted
+    pub fn algorithms(&self) -> &[Box<dyn PrimalityTest<u64>>] {
+        &self.algos
+    }
+}
+
+impl std::fmt::Debug for Chain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.algos.iter().map(|algo| algo.name()))
+            .finish()
+    }
+}
+
+impl PrimalityTest<u64> for Chain {
+    fn name(&self) -> &'static str {
+        "Chain"
+    }
+
+    fn is_prime(&self, n: u64) -> bool {
+        self.assess(n).verdict
+    }
+
+    fn estimate_cost(&self, n: u64) -> u64 {
+        // Worst case: every algorithm of the chain runs
+        self.algos
+            .iter()
+            .fold(0u64, |total, algo| total.saturating_add(algo.estimate_cost(n)))
+    }
+
+    fn describe(&self) -> AlgorithmInfo {
+        // The chain scales like its most expensive member
+        let complexity = self
+            .algos
+            .iter()
+            .map(|algo| algo.describe().complexity)
+            .max()
+            .unwrap_or(Complexity::Unknown);
+
+        AlgorithmInfo {
+            name: self.name(),
+            complexity,
+        }
+    }
+
+    fn assess(&self, n: u64) -> Assessment {
+        let mut last = Assessment {
+            verdict: false,
+            certainty: Certainty::HeuristicGuess,
+        };
+
+        for algo in &self.algos {
+            last = algo.assess(n);
+            if last.certainty == Certainty::Proven {
+                break;
+            }
+        }
+
+        // Nobody was sure: report the last (presumably strongest) opinion
+        last
+    }
+}
+
+/// Cheap screen that only settles the easy cases by trial division
+///
+/// Divides n by the primes up to `bound`. A factor proves n composite, and no
+/// factor proves n prime when n ≤ bound². Anything else is reported as a
+/// `HeuristicGuess` of "prime", which a `Chain` treats as a request to ask
+/// the next algorithm.
+#[derive(Clone, Debug)]
+pub struct TrialDivisionScreen {
+    bound: u64,
+    /// The primes up to `bound`, sieved once when the screen is built
+    primes: Vec<u64>,
+}
+
+impl TrialDivisionScreen {
+    /// Creates a screen dividing by the primes up to `bound`
+    ///
+    /// The trial divisors are sieved here, once, rather than on every query.
+    pub fn new(bound: u64) -> Self {
+        TrialDivisionScreen {
+            bound,
+            primes: primes_up_to(bound),
+        }
+    }
+}
+
+impl PrimalityTest<u64> for TrialDivisionScreen {
+    fn name(&self) -> &'static str {
+        "Trial Division Screen"
+    }
+
+    fn is_prime(&self, n: u64) -> bool {
+        self.assess(n).verdict
+    }
+
+    fn estimate_cost(&self, _n: u64) -> u64 {
+        // At most one division per prime below the bound, ~bound / ln(bound)
+        let bound = self.bound.max(2) as f64;
+        (bound / bound.ln()) as u64 + 1
+    }
+
+    fn describe(&self) -> AlgorithmInfo {
+        AlgorithmInfo {
+            name: self.name(),
+            complexity: Complexity::Heuristic,
+        }
+    }
+
+    fn assess(&self, n: u64) -> Assessment {
+        if let Some(verdict) = trivial_primality(n) {
+            return Assessment { verdict, certainty: Certainty::Proven };
+        }
+        if smallest_factor_among(n, &self.primes, self.bound).is_some() {
+            return Assessment { verdict: false, certainty: Certainty::Proven };
+        }
+
+        let certainty = if n <= self.bound.saturating_mul(self.bound) {
+            Certainty::Proven
+        } else {
+            Certainty::HeuristicGuess
+        };
+        Assessment { verdict: true, certainty }
+    }
+}
@@ -0,0 +1,418 @@
+use super::factor::mobius;
+use super::sieve::{odd_composite_flags, primes_up_to, sieve_segment};
+
+/// Euler-Mascheroni constant γ
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// Known exact values of pi(10^k) for k = 0..=12
+///
+/// Entry k is the number of primes up to 10^k, e.g. pi(10^6) = 78498.
+pub const PRIME_PI_AT_POWERS_OF_TEN: [u64; 13] = [
+    0,
+    4,
+    25,
+    168,
+    1_229,
+    9_592,
+    78_498,
+    664_579,
+    5_761_455,
+    50_847_534,
+    455_052_511,
+    4_118_054_813,
+    37_607_912_018,
+];
+
+/// Largest k for which `verify_pi_against_known` recomputes pi(10^k)
+///
+/// `prime_pi` sieves a flag per odd number, so 10^7 (5 MB of flags) is the
+/// largest power that stays quick; the larger table entries are reference only.
+const VERIFIED_POWERS_OF_TEN: usize = 7;
+
+/// Checks `prime_pi` against the embedded table of known values
+///
+/// Recomputes pi(10^k) for every k up to 7 and compares it with
+/// `PRIME_PI_AT_POWERS_OF_TEN`. This doubles as a self-test of the counting
+/// implementation.
+///
+/// # Returns
+///
+/// `true` if every recomputed value matches the table
+pub fn verify_pi_against_known() -> bool {
+    PRIME_PI_AT_POWERS_OF_TEN
+        .iter()
+        .take(VERIFIED_POWERS_OF_TEN + 1)
+        .zip(0u32..)
+        .all(|(&expected, k)| prime_pi(10u64.pow(k)) == expected)
+}
+
+/// Counts the primes less than or equal to `x`
+///
+/// This is the exact prime-counting function pi(x), computed with a
+/// Sieve of Eratosthenes over the odd numbers up to x.
+///
+/// # Arguments
+///
+/// * `x` - Inclusive upper bound
+///
+/// # Returns
+///
+/// The number of primes p with p ≤ x
+pub fn prime_pi(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+
+    let flags = odd_composite_flags(x);
+    // The number 2 plus every unmarked odd number except 1
+    1 + flags.iter().skip(1).filter(|&&composite| !composite).count() as u64
+}
+
+/// Counts the primes up to `x` with Meissel's combinatorial formula
+///
+/// Legendre's identity pi(x) = φ(x, a) + a - 1 - P2(x, a) with
+/// a = pi(∛x), where φ(y, a) counts the integers up to y free of the first
+/// a primes and P2(x, a) counts the integers up to x with exactly two prime
+/// factors, both above the a-th prime. φ is evaluated through the recursion
+/// φ(y, a) = φ(y, a - 1) - φ(y / p_a, a - 1), cut short as soon as every
+/// prime up to √y is among the first a, when φ(y, a) = pi(y) - a + 1.
+///
+/// Only the primes up to x^(2/3) are sieved, so this is much cheaper than
+/// `prime_pi` for large x: pi(10^10) needs primes below 4.7 million instead
+/// of a flag per odd number up to 10^10.
+///
+/// # Arguments
+///
+/// * `x` - Inclusive upper bound
+///
+/// # Returns
+///
+/// The number of primes p with p ≤ x, the same value as `prime_pi`
+///
+/// # References
+///
+/// See [Meissel-Lehmer algorithm](https://en.wikipedia.org/wiki/Meissel%E2%80%93Lehmer_algorithm)
+pub fn prime_pi_legendre(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+
+    let cbrt = integer_cbrt(x);
+    let sqrt = x.isqrt();
+    // Quotients x / p_i in P2 and the cut-off in φ stay below (∛x + 1)²
+    let primes = primes_up_to((cbrt + 1) * (cbrt + 1));
+    let pi = |y: u64| primes.partition_point(|&p| p <= y) as u64;
+
+    let a = pi(cbrt) as usize;
+    let b = pi(sqrt) as usize;
+    let p2: u64 = (a..b).map(|i| pi(x / primes[i]) - i as u64).sum();
+
+    legendre_phi(x, a, &primes, &pi) + a as u64 - 1 - p2
+}
+
+/// Legendre's φ(y, a), the count of 1 ≤ k ≤ y divisible by none of the first a primes
+fn legendre_phi(y: u64, a: usize, primes: &[u64], pi: &impl Fn(u64) -> u64) -> u64 {
+    if a == 0 || y == 0 {
+        return y;
+    }
+    let p = primes[a - 1];
+    if y < p {
+        // Only 1 survives
+        return 1;
+    }
+    if primes.get(a).is_some_and(|&next| next.saturating_mul(next) > y) {
+        return pi(y) - a as u64 + 1;
+    }
+    legendre_phi(y, a - 1, primes, pi) - legendre_phi(y / p, a - 1, primes, pi)
+}
+
+/// Integer cube root ⌊∛x⌋
+fn integer_cbrt(x: u64) -> u64 {
+    let mut r = (x as f64).cbrt() as u64;
+    while r.checked_pow(3).is_none_or(|cube| cube > x) {
+        r -= 1;
+    }
+    while (r + 1).checked_pow(3).is_some_and(|cube| cube <= x) {
+        r += 1;
+    }
+    r
+}
+
+/// Observed prime density in the window [center - window, center + window]
+///
+/// This is the empirical counterpart of the oscillation-based density used by
+/// the zeta module: the fraction of integers in the window that are prime,
+/// which the prime number theorem predicts to be close to 1 / ln(center).
+///
+/// # Arguments
+///
+/// * `center` - Middle of the window
+/// * `window` - Half-width of the window; the bounds saturate at 0 and u64::MAX
+///
+/// # Returns
+///
+/// The number of primes in the window divided by the number of integers in it
+pub fn local_prime_density(center: u64, window: u64) -> f64 {
+    let low = center.saturating_sub(window);
+    let high = center.saturating_add(window);
+
+    let base_primes = primes_up_to(high.isqrt());
+    let count = sieve_segment(low, high.saturating_add(1), &base_primes).len();
+
+    count as f64 / (high - low + 1) as f64
+}
+
+/// Logarithmic integral li(x)
+///
+/// The classic approximation of pi(x) suggested by Gauss, computed with
+/// Ramanujan's rapidly converging series:
+///
+/// li(x) = γ + ln ln x + √x × sum over n ≥ 1 of
+///         ((-1)^(n-1) (ln x)^n / (n! 2^(n-1))) × sum over k ≤ (n-1)/2 of 1/(2k+1)
+///
+/// # Arguments
+///
+/// * `x` - Point of evaluation, must be greater than 1
+pub fn logarithmic_integral(x: f64) -> f64 {
+    if x <= 1.0 {
+        return f64::NEG_INFINITY;
+    }
+
+    let ln_x = x.ln();
+    let mut sum = 0.0;
+    let mut term = ln_x; // (-1)^(n-1) (ln x)^n / (n! 2^(n-1))
+    let mut inner = 0.0; // sum of 1/(2k+1) for k ≤ (n-1)/2
+
+    for n in 1..200 {
+        if n % 2 == 1 {
+            inner += 1.0 / n as f64;
+        }
+
+        let contribution = term * inner;
+        sum += contribution;
+        if contribution.abs() < 1e-16 * sum.abs() {
+            break;
+        }
+
+        term *= -ln_x / (2.0 * (n + 1) as f64);
+    }
+
+    EULER_GAMMA + ln_x.ln() + x.sqrt() * sum
+}
+
+/// Riemann prime-counting function R(x)
+///
+/// A much sharper approximation of pi(x) than li(x), computed with the
+/// Gram series:
+///
+/// R(x) = 1 + sum over k ≥ 1 of (ln x)^k / (k! × k × zeta(k + 1))
+///
+/// # Arguments
+///
+/// * `x` - Point of evaluation, must be positive
+pub fn riemann_r(x: f64) -> f64 {
+    let ln_x = x.ln();
+    let mut sum = 1.0;
+    let mut term = 1.0; // (ln x)^k / k!
+
+    for k in 1..500 {
+        let k_f64 = k as f64;
+        term *= ln_x / k_f64;
+
+        let contribution = term / (k_f64 * zeta_real(k_f64 + 1.0));
+        sum += contribution;
+        if contribution.abs() < 1e-16 * sum.abs() {
+            break;
+        }
+    }
+
+    sum
+}
+
+/// Riemann prime-counting function R(x), summed through the Möbius function
+///
+/// Evaluates the defining series of R(x) directly:
+///
+/// R(x) = sum over k ≥ 1 of mu(k)/k × li(x^(1/k))
+///
+/// The terms with x^(1/k) < 2 are dropped, where li is small and soon
+/// undefined, which leaves the result within a small fraction of a unit of
+/// the Gram series in `riemann_r`. Either is a far better estimate of pi(x)
+/// than li(x) alone.
+///
+/// # Arguments
+///
+/// * `x` - Point of evaluation, must be at least 2
+pub fn prime_pi_riemann(x: f64) -> f64 {
+    (1u64..)
+        .map(|k| (k, x.powf(1.0 / k as f64)))
+        .take_while(|&(_, root)| root >= 2.0)
+        .map(|(k, root)| mobius(k) as f64 / k as f64 * logarithmic_integral(root))
+        .sum()
+}
+
+/// Compares the exact prime count at `x` with its classic approximations
+///
+/// # Arguments
+///
+/// * `x` - Inclusive upper bound
+///
+/// # Returns
+///
+/// A tuple `(pi(x), R(x), li(x))` with the exact count, the Riemann R
+/// approximation and the logarithmic integral
+pub fn prime_count_with_bounds(x: u64) -> (u64, f64, f64) {
+    let x_f64 = x as f64;
+    (prime_pi(x), riemann_r(x_f64), logarithmic_integral(x_f64))
+}
+
+/// Approximate inverse of the prime-counting function
+///
+/// Finds the smallest x with pi(x) = target, which is the target-th prime.
+/// The region is first bounded with Rosser's estimate
+/// p_n < n (ln n + ln ln n) (valid for n ≥ 6), then sieved to pin down the
+/// exact value. Handy for sizing a sieve that must hold a given number of primes.
+///
+/// # Arguments
+///
+/// * `target` - Desired prime count
+///
+/// # Returns
+///
+/// The smallest x such that pi(x) = target, or 0 when target is 0
+pub fn pi_inverse(target: u64) -> u64 {
+    if target == 0 {
+        return 0;
+    }
+
+    let bound = if target < 6 {
+        // p_5 = 11, below the range of Rosser's estimate
+        11
+    } else {
+        let n = target as f64;
+        (n * (n.ln() + n.ln().ln())).ceil() as u64
+    };
+
+    primes_up_to(bound)[(target - 1) as usize]
+}
+
+/// Mertens function M(n), the sum of the Möbius function μ(k) for 1 ≤ k ≤ n
+///
+/// The values of μ come from a linear sieve, which visits every composite
+/// exactly once through its smallest prime factor, so the cost is O(n) time
+/// and memory. The Riemann hypothesis is equivalent to |M(n)| growing no
+/// faster than n^(1/2 + ε) for every ε > 0.
+///
+/// # Arguments
+///
+/// * `n` - Inclusive upper bound of the sum
+///
+/// # Returns
+///
+/// M(n), which is 0 for n = 0
+///
+/// # References
+///
+/// See [Mertens function](https://en.wikipedia.org/wiki/Mertens_function)
+pub fn mertens(n: u64) -> i64 {
+    mobius_sieve(n).iter().map(|&mu| mu as i64).sum()
+}
+
+/// Summatory Liouville function L(n) = Σ λ(k) for 1 ≤ k ≤ n
+///
+/// λ is completely multiplicative, so a linear sieve gets λ(i × p) as -λ(i)
+/// for every prime p up to the smallest factor of i, in O(n) time and memory.
+/// Pólya conjectured that L(n) ≤ 0 for every n ≥ 2; the first
+/// counterexample is 906,150,257, far beyond what this sum can reach here.
+///
+/// # Arguments
+///
+/// * `limit` - Inclusive upper bound of the sum
+///
+/// # Returns
+///
+/// L(limit), which is 0 for limit = 0
+///
+/// # References
+///
+/// See [Liouville function](https://en.wikipedia.org/wiki/Liouville_function)
+pub fn liouville_sum(limit: u64) -> i64 {
+    let len = limit as usize + 1;
+    let mut lambda = vec![0i8; len];
+    let mut primes: Vec<usize> = Vec::new();
+    if limit >= 1 {
+        lambda[1] = 1;
+    }
+
+    for i in 2..len {
+        if lambda[i] == 0 {
+            primes.push(i);
+            lambda[i] = -1;
+        }
+        for &p in &primes {
+            let Some(multiple) = i.checked_mul(p).filter(|&m| m < len) else {
+                break;
+            };
+            lambda[multiple] = -lambda[i];
+            if i.is_multiple_of(p) {
+                break;
+            }
+        }
+    }
+
+    lambda.iter().map(|&l| l as i64).sum()
+}
+
+/// Möbius function μ(k) for every 0 ≤ k ≤ `limit`
+///
+/// Uses the linear sieve described in `mertens`; for a single value,
+/// `mobius` factorizes instead.
+///
+/// # Returns
+///
+/// A vector of length `limit + 1` whose entry k is μ(k), with μ(0) taken as 0
+pub fn mobius_sieve(limit: u64) -> Vec<i8> {
+    let len = limit as usize + 1;
+    let mut mu = vec![0i8; len];
+    let mut composite = vec![false; len];
+    let mut primes: Vec<usize> = Vec::new();
+    if limit >= 1 {
+        mu[1] = 1;
+    }
+
+    for i in 2..len {
+        if !composite[i] {
+            primes.push(i);
+            mu[i] = -1;
+        }
+        for &p in &primes {
+            let Some(multiple) = i.checked_mul(p).filter(|&m| m < len) else {
+                break;
+            };
+            composite[multiple] = true;
+            if i.is_multiple_of(p) {
+                // p² divides the multiple
+                break;
+            }
+            mu[multiple] = -mu[i];
+        }
+    }
+
+    mu
+}
+
+/// Riemann zeta function for real s > 1
+///
+/// Sums the first terms directly and approximates the tail with the
+/// Euler-Maclaurin formula, which is accurate to well below 1e-10 for s ≥ 2.
+fn zeta_real(s: f64) -> f64 {
+    const TERMS: u32 = 10;
+
+    let mut sum = 0.0;
+    for n in 1..TERMS {
+        sum += (n as f64).powf(-s);
+    }
+
+    let n = TERMS as f64;
+    sum + n.powf(1.0 - s) / (s - 1.0) + 0.5 * n.powf(-s) + s * n.powf(-s - 1.0) / 12.0
+}
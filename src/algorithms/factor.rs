@@ -0,0 +1,783 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use super::arith::{is_perfect_power, mod_sqrt, ModArith};
+use super::miller_rabin::is_prime_miller_rabin;
+use super::sieve::{primes_up_to, smallest_factor_sieve};
+
+/// Primes below this bound are stripped by trial division before running rho
+const TRIAL_DIVISION_BOUND: u64 = 1000;
+
+/// Number of polynomial constants c tried in x² + c before giving up on rho
+const MAX_POLYNOMIALS: u64 = 16;
+
+/// Budget of a single Brent run, per polynomial, in evaluations of x² + c
+const MAX_ITERATIONS: u64 = 1 << 24;
+
+/// Number of steps whose differences are multiplied together before each gcd
+const GCD_BATCH: u64 = 128;
+
+/// Primes up to this bound are sieved once and cached for `has_small_factor`
+const SMALL_PRIME_CACHE_LIMIT: u64 = 1 << 16;
+
+/// Cached table of the primes up to `SMALL_PRIME_CACHE_LIMIT`
+static SMALL_PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+
+/// Looks for a prime factor of n among the primes up to `bound`
+///
+/// This is the cheap pre-screen to run before an expensive primality test:
+/// most composites have a small factor, and finding it settles the question
+/// at once. Primes up to 2^16 come from a table sieved once per process;
+/// larger bounds sieve the missing primes on each call.
+///
+/// # Arguments
+///
+/// * `n` - The number to screen
+/// * `bound` - Inclusive upper bound of the trial divisors
+///
+/// # Returns
+///
+/// `Some(p)` with the smallest prime p ≤ bound that divides n, with p < n, or
+/// `None` if there is none (in particular when n is 0, 1 or a prime)
+pub fn has_small_factor(n: u64, bound: u64) -> Option<u64> {
+    let table = SMALL_PRIMES.get_or_init(|| primes_up_to(SMALL_PRIME_CACHE_LIMIT));
+    let extended;
+    let primes = if bound <= SMALL_PRIME_CACHE_LIMIT {
+        table.as_slice()
+    } else {
+        extended = primes_up_to(bound);
+        extended.as_slice()
+    };
+
+    smallest_factor_among(n, primes, bound)
+}
+
+/// Finds the smallest prime p ≤ bound in the ascending table `primes` that
+/// divides n with p < n, as `has_small_factor` does
+pub(crate) fn smallest_factor_among(n: u64, primes: &[u64], bound: u64) -> Option<u64> {
+    primes
+        .iter()
+        .take_while(|&&p| p <= bound && p.saturating_mul(p) <= n)
+        .copied()
+        .find(|&p| n.is_multiple_of(p))
+}
+
+/// Factors n into primes
+///
+/// Small factors are removed by trial division, then the remaining cofactor is
+/// split recursively with Pollard's rho using Brent's cycle detection. When a
+/// run does not find a factor within its iteration budget, rho is retried with
+/// another polynomial constant; if every polynomial fails, trial division
+/// finishes the residual so the result is always complete.
+///
+/// # Arguments
+///
+/// * `n` - The number to factor
+///
+/// # Returns
+///
+/// The prime factors of n in ascending order, repeated according to their
+/// multiplicity. 0 and 1 have no prime factors and yield an empty vector.
+///
+/// # Performance
+///
+/// - Expected time: O(n^(1/4)) modular multiplications for the largest split
+/// - Space complexity: O(number of factors)
+///
+/// # References
+///
+/// See [Pollard's rho algorithm](https://en.wikipedia.org/wiki/Pollard%27s_rho_algorithm)
+/// and R. P. Brent, "An improved Monte Carlo factorization algorithm" (1980)
+pub fn factorize(n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    if n < 2 {
+        return factors;
+    }
+
+    let mut m = n;
+    let mut p = 2;
+    while p < TRIAL_DIVISION_BOUND && p * p <= m {
+        while m.is_multiple_of(p) {
+            factors.push(p);
+            m /= p;
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+
+    if m > 1 {
+        split(m, &mut factors);
+    }
+
+    factors.sort_unstable();
+    factors
+}
+
+/// Factors n into (prime, exponent) pairs
+///
+/// # Returns
+///
+/// The distinct prime factors of n in ascending order, each with its
+/// multiplicity; empty for 0 and 1
+pub fn factorize_powers(n: u64) -> Vec<(u64, u32)> {
+    let mut powers: Vec<(u64, u32)> = Vec::new();
+    for p in factorize(n) {
+        match powers.last_mut() {
+            Some((last, exponent)) if *last == p => *exponent += 1,
+            _ => powers.push((p, 1)),
+        }
+    }
+    powers
+}
+
+/// A number together with its prime factorization
+///
+/// With the `serde` feature it implements `Serialize` and `Deserialize`, so
+/// factorizations can be cached or sent elsewhere. A deserialized value is
+/// not checked; compare `product()` with `n` to validate it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Factorization {
+    /// The factored number
+    pub n: u64,
+    /// Distinct prime factors in ascending order, each with its multiplicity
+    pub factors: Vec<(u64, u32)>,
+}
+
+impl Factorization {
+    /// Multiplies the factors back together
+    ///
+    /// # Returns
+    ///
+    /// The product of p^e over the factors, which is `n` for a valid
+    /// factorization of n ≥ 1, or `None` if it overflows a u64. The empty
+    /// factorization of 0 has product 1.
+    pub fn product(&self) -> Option<u64> {
+        self.factors
+            .iter()
+            .try_fold(1u64, |product, &(p, e)| p.checked_pow(e).and_then(|power| product.checked_mul(power)))
+    }
+}
+
+/// Factors n into a `Factorization`
+///
+/// The same (prime, exponent) pairs as `factorize_powers`, kept together
+/// with n.
+pub fn factorize_struct(n: u64) -> Factorization {
+    Factorization {
+        n,
+        factors: factorize_powers(n),
+    }
+}
+
+/// Alias of `factorize`, named as the flat counterpart of `factorize_powers`
+///
+/// Returns the prime factors of n in ascending order, repeated according to
+/// their multiplicity, so 360 gives [2, 2, 2, 3, 3, 5].
+pub fn factorize_flat(n: u64) -> Vec<u64> {
+    factorize(n)
+}
+
+/// Lists the distinct prime factors of n
+///
+/// # Returns
+///
+/// The primes dividing n in ascending order, each once; empty for 0 and 1
+pub fn distinct_prime_factors(n: u64) -> Vec<u64> {
+    factorize_powers(n).into_iter().map(|(p, _)| p).collect()
+}
+
+/// Prime signature of n, the exponents of its factorization in descending order
+///
+/// Numbers with the same signature share their multiplicative structure,
+/// e.g. the number of divisors, whatever the primes involved: 360 = 2³ × 3² × 5
+/// and 2250 = 2 × 3² × 5³ both have signature [3, 2, 1].
+///
+/// # Returns
+///
+/// The exponents sorted from largest to smallest; empty for 0 and 1
+pub fn prime_signature(n: u64) -> Vec<u32> {
+    let mut exponents: Vec<u32> = factorize_powers(n).into_iter().map(|(_, e)| e).collect();
+    exponents.sort_unstable_by(|a, b| b.cmp(a));
+    exponents
+}
+
+/// Radical of n, the product of its distinct prime factors
+///
+/// Also called the squarefree kernel: the largest squarefree divisor of n,
+/// so rad(360) = rad(2³ × 3² × 5) = 30.
+///
+/// # Returns
+///
+/// rad(n), which is 1 for n = 1 and taken as 0 for n = 0
+pub fn radical(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    distinct_prime_factors(n).iter().product()
+}
+
+/// Counts the positive divisors of n, d(n)
+///
+/// Computed from the factorization n = p1^e1 × ... × pk^ek as the product of
+/// the (ei + 1).
+///
+/// # Returns
+///
+/// The number of divisors of n, or 0 when n is 0
+pub fn count_divisors(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    factorize_powers(n).iter().map(|&(_, e)| e as u64 + 1).product()
+}
+
+/// Lists the positive divisors of n
+///
+/// Generated from the factorization n = p1^e1 × ... × pk^ek by multiplying
+/// out every choice of exponents 0..=ei, so it takes d(n) multiplications
+/// after factoring rather than a scan up to √n.
+///
+/// # Returns
+///
+/// The divisors of n in ascending order, or an empty vector when n is 0
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut divisors = vec![1];
+    for (p, e) in factorize_powers(n) {
+        let known = divisors.len();
+        let mut power = 1;
+        for _ in 0..e {
+            power *= p;
+            for i in 0..known {
+                divisors.push(divisors[i] * power);
+            }
+        }
+    }
+
+    divisors.sort_unstable();
+    divisors
+}
+
+/// Möbius function μ(n)
+///
+/// Computed from the factorization: μ(n) is 0 when a squared prime divides
+/// n, and (-1)^k when n is a product of k distinct primes. Use
+/// `mobius_sieve` for every value up to a limit at once.
+///
+/// # Returns
+///
+/// -1, 0 or 1; μ(1) is 1 and μ(0) is taken as 0
+pub fn mobius(n: u64) -> i8 {
+    if n == 0 {
+        return 0;
+    }
+    let powers = factorize_powers(n);
+    match powers.iter().all(|&(_, e)| e == 1) {
+        false => 0,
+        true if powers.len().is_multiple_of(2) => 1,
+        true => -1,
+    }
+}
+
+/// Number of prime factors of n counted with multiplicity, Ω(n)
+///
+/// # Returns
+///
+/// The sum of the exponents of the factorization; Ω(1) and Ω(0) are 0
+pub fn big_omega(n: u64) -> u32 {
+    factorize_powers(n).iter().map(|&(_, e)| e).sum()
+}
+
+/// Liouville function λ(n) = (-1)^Ω(n)
+///
+/// Unlike μ, λ is completely multiplicative and never 0 on positive n: it is
+/// 1 when n has an even number of prime factors counted with multiplicity.
+/// Use `liouville_sum` for its running sum.
+///
+/// # Returns
+///
+/// -1 or 1; λ(1) is 1 and λ(0) is taken as 0
+pub fn liouville(n: u64) -> i8 {
+    match n {
+        0 => 0,
+        _ if big_omega(n).is_multiple_of(2) => 1,
+        _ => -1,
+    }
+}
+
+/// Von Mangoldt function Λ(n)
+///
+/// Λ(n) is ln p when n = p^k is a power of a prime p, and 0 otherwise. Its
+/// running sum is the Chebyshev function ψ(x) of the explicit formula.
+///
+/// # Returns
+///
+/// ln p for a prime power p^k, 0.0 for every other n, including 0 and 1
+pub fn von_mangoldt(n: u64) -> f64 {
+    match factorize_powers(n).as_slice() {
+        [(p, _)] => (*p as f64).ln(),
+        _ => 0.0,
+    }
+}
+
+/// Sums the positive divisors of n, σ(n)
+///
+/// Computed from the factorization as the product of
+/// (p^(e+1) - 1) / (p - 1) over the prime powers p^e dividing n. The result is
+/// a u128 because σ(n) exceeds u64::MAX for some n close to it.
+///
+/// # Returns
+///
+/// The sum of the divisors of n, or 0 when n is 0
+pub fn sum_divisors(n: u64) -> u128 {
+    if n == 0 {
+        return 0;
+    }
+    divisor_sum(&factorize_powers(n))
+}
+
+/// σ of the integer with the factorization `powers`
+fn divisor_sum(powers: &[(u64, u32)]) -> u128 {
+    powers
+        .iter()
+        .map(|&(p, e)| {
+            let p = p as u128;
+            (p.pow(e + 1) - 1) / (p - 1)
+        })
+        .product()
+}
+
+/// Writes n as a sum of two squares a² + b², if possible
+///
+/// By Fermat's theorem on sums of two squares, a prime p is such a sum iff
+/// p = 2 or p ≡ 1 (mod 4), and n is one iff every prime q ≡ 3 (mod 4) divides
+/// it to an even power. Each prime p ≡ 1 (mod 4) is split with a square root
+/// of -1 modulo p (`mod_sqrt`) followed by the Euclidean algorithm
+/// (Hermite-Serret), and the prime decompositions are combined with the
+/// Brahmagupta-Fibonacci identity
+/// (a² + b²)(c² + d²) = (ac - bd)² + (ad + bc)².
+///
+/// # Returns
+///
+/// `Some((a, b))` with a ≤ b and a² + b² = n, or `None` if n is not a sum of
+/// two squares
+pub fn sum_of_two_squares(n: u64) -> Option<(u64, u64)> {
+    if n == 0 {
+        return Some((0, 0));
+    }
+
+    let mut pair = (1u128, 0u128);
+    for (p, e) in factorize_powers(n) {
+        if p % 4 == 3 {
+            if e % 2 == 1 {
+                return None;
+            }
+            // q^(2f) = (q^f)² + 0² just scales both components
+            let scale = (p as u128).pow(e / 2);
+            pair = (pair.0 * scale, pair.1 * scale);
+            continue;
+        }
+
+        let (c, d) = if p == 2 { (1, 1) } else { prime_two_squares(p) };
+        for _ in 0..e {
+            let (a, b) = pair;
+            pair = ((a * c).abs_diff(b * d), a * d + b * c);
+        }
+    }
+
+    let (a, b) = (pair.0 as u64, pair.1 as u64);
+    Some((a.min(b), a.max(b)))
+}
+
+/// Splits a prime p ≡ 1 (mod 4) as a² + b² by the Hermite-Serret method
+fn prime_two_squares(p: u64) -> (u128, u128) {
+    let root = mod_sqrt(p - 1, p).expect("-1 is a square modulo a prime p ≡ 1 (mod 4)");
+    let (mut a, mut b) = (p, root);
+    while b.saturating_mul(b) > p {
+        (a, b) = (b, a % b);
+    }
+    let c = (p - b * b).isqrt();
+    (b as u128, c as u128)
+}
+
+/// Classification of an integer by the sum of its proper divisors
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NumberClass {
+    /// The proper divisors sum to exactly n, e.g. 6 = 1 + 2 + 3
+    Perfect,
+    /// The proper divisors sum to more than n, e.g. 12
+    Abundant,
+    /// The proper divisors sum to less than n, e.g. every prime power
+    Deficient,
+}
+
+/// Classifies n as perfect, abundant or deficient
+///
+/// Compares σ(n) with 2n, which is the same as comparing the sum of the proper
+/// divisors with n.
+///
+/// # Returns
+///
+/// The class of n; 0, which has no well-defined divisor sum, is reported as
+/// `Deficient`
+pub fn number_class(n: u64) -> NumberClass {
+    class_of(n, &factorize_powers(n))
+}
+
+/// Perfect, abundant or deficient for n with the factorization `powers`
+fn class_of(n: u64, powers: &[(u64, u32)]) -> NumberClass {
+    if n == 0 {
+        return NumberClass::Deficient;
+    }
+    match divisor_sum(powers).cmp(&(2 * n as u128)) {
+        std::cmp::Ordering::Equal => NumberClass::Perfect,
+        std::cmp::Ordering::Greater => NumberClass::Abundant,
+        std::cmp::Ordering::Less => NumberClass::Deficient,
+    }
+}
+
+/// Tests whether n is a Carmichael number
+///
+/// Uses Korselt's criterion: n is a Carmichael number exactly when it is
+/// composite, squarefree and p - 1 divides n - 1 for every prime p dividing
+/// n. These are the composites that pass Fermat's test to every coprime base.
+///
+/// # Returns
+///
+/// `true` for 561, 1105, 1729, ..., `false` for every other n
+pub fn is_carmichael(n: u64) -> bool {
+    korselt(n, &factorize_powers(n))
+}
+
+/// Korselt's criterion for n with the factorization `powers`
+fn korselt(n: u64, powers: &[(u64, u32)]) -> bool {
+    powers.len() >= 2 && powers.iter().all(|&(p, e)| e == 1 && (n - 1).is_multiple_of(p - 1))
+}
+
+/// Everything `classify_number` knows about an integer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberSummary {
+    /// The described integer
+    pub n: u64,
+    /// `true` if n is prime
+    pub is_prime: bool,
+    /// Prime factorization as (prime, exponent) pairs, as in `factorize_powers`
+    pub factors: Vec<(u64, u32)>,
+    /// Number of positive divisors d(n)
+    pub divisor_count: u64,
+    /// `true` if n is a Carmichael number
+    pub is_carmichael: bool,
+    /// n as b^k with the largest k ≥ 2, as in `is_perfect_power`
+    pub perfect_power: Option<(u64, u32)>,
+    /// Whether n is perfect, abundant or deficient
+    pub class: NumberClass,
+}
+
+/// Describes n with every number-theoretic property of this module at once
+///
+/// Factors n a single time and derives the other properties from the
+/// factorization where possible, which makes it cheaper than calling each
+/// function in turn; handy for a "tell me about n" display.
+///
+/// # Returns
+///
+/// A `NumberSummary` of n. 0 has no factors and no divisor count, and is
+/// reported as deficient like `number_class` does.
+pub fn classify_number(n: u64) -> NumberSummary {
+    let factors = factorize_powers(n);
+    let divisor_count = match n {
+        0 => 0,
+        _ => factors.iter().map(|&(_, e)| e as u64 + 1).product(),
+    };
+
+    NumberSummary {
+        n,
+        is_prime: matches!(factors.as_slice(), [(_, 1)]),
+        divisor_count,
+        is_carmichael: korselt(n, &factors),
+        perfect_power: is_perfect_power(n),
+        class: class_of(n, &factors),
+        factors,
+    }
+}
+
+/// Classification of an integer as a prime, a prime power or neither
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PrimePowerClass {
+    /// n is prime
+    Prime,
+    /// n = base^exp for a prime base and an exp ≥ 2
+    PrimePower {
+        /// The prime base
+        base: u64,
+        /// The exponent, at least 2
+        exp: u32,
+    },
+    /// n has two distinct prime factors, or is 0 or 1
+    Composite,
+}
+
+/// Tells primes and prime powers apart from other integers
+///
+/// The prime powers are exactly the orders of finite fields. n is first tested
+/// for primality, then written as b^k with the largest possible k by
+/// `is_perfect_power`; n is a prime power when that b is prime.
+///
+/// # Returns
+///
+/// The class of n; 0 and 1, which are neither prime nor prime powers, are
+/// reported as `Composite`
+pub fn classify_prime_power(n: u64) -> PrimePowerClass {
+    if is_prime_miller_rabin(n, 0) {
+        return PrimePowerClass::Prime;
+    }
+    match is_perfect_power(n) {
+        Some((base, exp)) if is_prime_miller_rabin(base, 0) => PrimePowerClass::PrimePower { base, exp },
+        _ => PrimePowerClass::Composite,
+    }
+}
+
+/// Node of the factorization tree built by `factor_tree`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FactorNode {
+    /// A prime, or the whole tree of 0 or 1, which have no prime factors
+    Leaf(u64),
+    /// A composite value split into two factors whose product it is
+    Split {
+        /// The composite value
+        value: u64,
+        /// The first factor found
+        left: Box<FactorNode>,
+        /// The complementary factor, value / left
+        right: Box<FactorNode>,
+    },
+}
+
+impl FactorNode {
+    /// Returns the value at this node
+    pub fn value(&self) -> u64 {
+        match self {
+            FactorNode::Leaf(value) | FactorNode::Split { value, .. } => *value,
+        }
+    }
+
+    /// Returns the leaves from left to right, the prime factors of the root
+    pub fn leaves(&self) -> Vec<u64> {
+        match self {
+            FactorNode::Leaf(value) => vec![*value],
+            FactorNode::Split { left, right, .. } => {
+                let mut leaves = left.leaves();
+                leaves.extend(right.leaves());
+                leaves
+            }
+        }
+    }
+
+    /// Writes this node at the given depth, then its children one level deeper
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}{}", "", self.value(), indent = 2 * depth)?;
+        if let FactorNode::Split { left, right, .. } = self {
+            left.write_indented(f, depth + 1)?;
+            right.write_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FactorNode {
+    /// Renders one value per line, each child indented two spaces below its parent
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// Builds the tree of successive two-factor splits of n down to its primes
+///
+/// Each composite is split into a divisor and its cofactor: a prime below
+/// 1000 when there is one, found by trial division, and otherwise a divisor
+/// from Pollard's rho as in `factorize`. The leaves, read left to right, are
+/// the prime factors of n with multiplicity, though not necessarily in
+/// ascending order. `Display` renders the tree with indentation.
+///
+/// # Returns
+///
+/// The root of the tree, a single leaf when n is prime, 0 or 1
+pub fn factor_tree(n: u64) -> FactorNode {
+    if n < 4 || is_prime_miller_rabin(n, 0) {
+        return FactorNode::Leaf(n);
+    }
+
+    let divisor = has_small_factor(n, TRIAL_DIVISION_BOUND).unwrap_or_else(|| find_divisor(n));
+    FactorNode::Split {
+        value: n,
+        left: Box::new(factor_tree(divisor)),
+        right: Box::new(factor_tree(n / divisor)),
+    }
+}
+
+/// Errors reported by `pollard_rho_bounded`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FactorError {
+    /// The iteration budget ran out before a divisor was found
+    Budget {
+        /// The budget that was spent
+        iterations: u64,
+    },
+}
+
+impl fmt::Display for FactorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FactorError::Budget { iterations } => {
+                write!(f, "no divisor found within {} rho iterations", iterations)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FactorError {}
+
+/// Looks for a divisor of n with Pollard's rho under an iteration budget
+///
+/// Runs Brent's variant on x² + c for c = 1, 2, ..., moving to the next
+/// polynomial whenever one degenerates, until a divisor turns up or
+/// `max_iters` iterations have been spent in total. An iteration is one
+/// evaluation of the polynomial; the only work beyond the budget is replaying
+/// a single batch of at most 128 steps when a gcd overshoots. Unlike
+/// `factorize`, which falls back to trial division, this stops when the budget
+/// runs out, so a caller facing adversarial inputs decides what to do next.
+///
+/// # Arguments
+///
+/// * `n` - The number to split
+/// * `max_iters` - Total number of polynomial evaluations allowed, over all
+///   polynomials
+///
+/// # Returns
+///
+/// `Ok(Some(d))` with a divisor 1 < d < n, not necessarily prime, or
+/// `Ok(None)` if n is prime, 0 or 1 and has no such divisor
+///
+/// # Errors
+///
+/// Returns `FactorError::Budget` if n is composite and no divisor was found
+/// within `max_iters` iterations
+pub fn pollard_rho_bounded(n: u64, max_iters: u64) -> Result<Option<u64>, FactorError> {
+    if n < 2 || is_prime_miller_rabin(n, 0) {
+        return Ok(None);
+    }
+    if n.is_multiple_of(2) {
+        return Ok(Some(2));
+    }
+
+    let mut budget = max_iters;
+    for c in 1.. {
+        if budget == 0 {
+            break;
+        }
+        if let Some(divisor) = pollard_rho_brent(n, c, &mut budget) {
+            return Ok(Some(divisor));
+        }
+    }
+
+    Err(FactorError::Budget { iterations: max_iters })
+}
+
+/// Recursively splits a cofactor free of small primes into prime factors
+fn split(n: u64, factors: &mut Vec<u64>) {
+    if is_prime_miller_rabin(n, 0) {
+        factors.push(n);
+        return;
+    }
+
+    let divisor = find_divisor(n);
+    split(divisor, factors);
+    split(n / divisor, factors);
+}
+
+/// Finds a nontrivial divisor of a composite free of small primes
+fn find_divisor(n: u64) -> u64 {
+    (1..=MAX_POLYNOMIALS)
+        .find_map(|c| {
+            let mut budget = MAX_ITERATIONS;
+            pollard_rho_brent(n, c, &mut budget)
+        })
+        .or_else(|| smallest_factor_sieve(n))
+        .expect("a composite number has a nontrivial divisor")
+}
+
+/// Pollard's rho with Brent's cycle detection on f(x) = x² + c mod n
+///
+/// Differences are accumulated into a running product and only reduced with a
+/// gcd every `GCD_BATCH` steps; if a batch overshoots to gcd = n, the last
+/// batch is replayed one step at a time.
+///
+/// Every evaluation of f is deducted from `budget`; the last round is cut short
+/// to fit what is left, and the run stops once the budget is spent.
+///
+/// # Returns
+///
+/// `Some(d)` with a nontrivial divisor 1 < d < n, or `None` if the polynomial
+/// degenerated or the iteration budget ran out
+fn pollard_rho_brent(n: u64, c: u64, budget: &mut u64) -> Option<u64> {
+    let f = |x: u64| x.mul_mod(x, n).add_mod(c % n, n);
+
+    let mut y = 2 % n;
+    let mut x = y;
+    let mut ys = y;
+    let mut q = 1u64;
+    let mut g = 1u64;
+    let mut r = 1u64;
+
+    while g == 1 {
+        // A round advances y by r steps and then scans up to r more
+        let steps = r.min(*budget / 2);
+        if steps == 0 {
+            *budget = 0;
+            return None;
+        }
+
+        x = y;
+        for _ in 0..steps {
+            y = f(y);
+        }
+
+        let mut k = 0;
+        while k < steps && g == 1 {
+            ys = y;
+            for _ in 0..GCD_BATCH.min(steps - k) {
+                y = f(y);
+                q = q.mul_mod(x.abs_diff(y), n);
+            }
+            g = gcd(q, n);
+            k += GCD_BATCH;
+        }
+
+        *budget -= 2 * steps;
+        r *= 2;
+    }
+
+    if g == n {
+        // The batch overshot: replay it one step at a time
+        loop {
+            ys = f(ys);
+            g = gcd(x.abs_diff(ys), n);
+            if g > 1 {
+                break;
+            }
+        }
+    }
+
+    if g == n { None } else { Some(g) }
+}
+
+/// Greatest common divisor by the Euclidean algorithm
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
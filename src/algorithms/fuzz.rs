@@ -0,0 +1,87 @@
+use super::miller_rabin::is_prime_miller_rabin;
+use super::rng::{uniform_below, SplitMix64};
+use super::PrimalityRegistry;
+
+/// A registered algorithm disagreeing with the oracle on one input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// The input on which the verdicts differ
+    pub n: u64,
+    /// Name of the algorithm that got it wrong
+    pub algorithm: &'static str,
+    /// The oracle's verdict: `true` if n is prime
+    pub expected: bool,
+}
+
+/// Outcome of a `fuzz_algorithms` run
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FuzzReport {
+    /// Number of random inputs drawn
+    pub iterations: usize,
+    /// Number of (input, algorithm) pairs compared with the oracle
+    pub checks: usize,
+    /// Number of (input, algorithm) pairs skipped because the input exceeds
+    /// the algorithm's `max_reliable` bound
+    pub skipped: usize,
+    /// Every disagreement found, in the order the inputs were drawn
+    pub divergences: Vec<Divergence>,
+}
+
+impl FuzzReport {
+    /// Returns `true` if no algorithm disagreed with the oracle
+    pub fn is_clean(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Cross-checks every registered algorithm against Miller-Rabin on random inputs
+///
+/// Draws `iterations` values uniformly from [0, max_n] and compares the
+/// verdict of each algorithm of `PrimalityRegistry::with_all_algorithms`
+/// with the deterministic Miller-Rabin test, which is exact for u64. Inputs
+/// that an algorithm refuses through `try_is_prime` are counted as skipped
+/// rather than as divergences.
+///
+/// The trial-division sieve is part of the registry, so `max_n` should stay
+/// where O(√n) divisions per input are affordable.
+///
+/// # Arguments
+///
+/// * `iterations` - Number of random inputs to draw
+/// * `max_n` - Inclusive upper bound of the inputs
+/// * `seed` - Seed of the generator; the same seed replays the same inputs
+///
+/// # Returns
+///
+/// A `FuzzReport` listing every divergence with the offending input
+pub fn fuzz_algorithms(iterations: usize, max_n: u64, seed: u64) -> FuzzReport {
+    let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+    let mut rng = SplitMix64::new(seed);
+    let mut report = FuzzReport {
+        iterations,
+        ..FuzzReport::default()
+    };
+
+    for _ in 0..iterations {
+        let n = uniform_below(&mut rng, max_n as u128 + 1) as u64;
+        let expected = is_prime_miller_rabin(n, 0);
+
+        for algo in registry.algorithms() {
+            match algo.try_is_prime(n) {
+                Ok(verdict) => {
+                    report.checks += 1;
+                    if verdict != expected {
+                        report.divergences.push(Divergence {
+                            n,
+                            algorithm: algo.name(),
+                            expected,
+                        });
+                    }
+                }
+                Err(_) => report.skipped += 1,
+            }
+        }
+    }
+
+    report
+}
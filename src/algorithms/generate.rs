@@ -0,0 +1,167 @@
+use std::fmt;
+
+use super::miller_rabin::is_prime_miller_rabin;
+use super::rng::{uniform_below, RandomSource};
+use super::special_forms::{is_safe_prime, is_sophie_germain_prime};
+
+/// Default number of candidates `PrimeGenerator::generate` tries before giving up
+pub const DEFAULT_MAX_ATTEMPTS: usize = 100_000;
+
+/// Errors reported by `PrimeGenerator::generate`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GenError {
+    /// The requested bit length is outside 2..=64
+    InvalidBits(u32),
+    /// The congruence modulus is zero
+    ZeroModulus,
+    /// No value of the requested bit length satisfies the congruence
+    NoCandidates,
+    /// Every candidate drawn within the attempt budget failed a constraint
+    Exhausted {
+        /// Number of candidates tried
+        attempts: usize,
+    },
+}
+
+impl fmt::Display for GenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenError::InvalidBits(bits) => write!(f, "bit length {} is outside 2..=64", bits),
+            GenError::ZeroModulus => f.write_str("congruence modulus must be non-zero"),
+            GenError::NoCandidates => f.write_str("no value of the requested bit length satisfies the congruence"),
+            GenError::Exhausted { attempts } => {
+                write!(f, "no prime satisfying every constraint found in {} attempts", attempts)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenError {}
+
+/// Builder for random primes satisfying a set of constraints
+///
+/// Each constraint narrows the candidates: a bit length and a congruence
+/// p ≡ r (mod m) shape how candidates are drawn, while the primality, safe
+/// prime and Sophie Germain conditions are checked with Miller-Rabin on each
+/// draw. Candidates are drawn until one passes or the attempt budget runs
+/// out, so a constraint set with no solution ends in `GenError::Exhausted`
+/// rather than a hang.
+#[derive(Clone, Debug)]
+pub struct PrimeGenerator {
+    bits: Option<u32>,
+    congruence: (u64, u64),
+    safe: bool,
+    sophie_germain: bool,
+    max_attempts: usize,
+}
+
+impl PrimeGenerator {
+    /// Creates a generator of unconstrained u64 primes
+    pub fn new() -> Self {
+        PrimeGenerator {
+            bits: None,
+            congruence: (0, 1),
+            safe: false,
+            sophie_germain: false,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    /// Requires the prime to have exactly `bits` significant bits
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - Bit length in 2..=64, so the prime lies in [2^(bits-1), 2^bits)
+    pub fn bits(mut self, bits: u32) -> Self {
+        self.bits = Some(bits);
+        self
+    }
+
+    /// Requires p ≡ r (mod m)
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - The residue, reduced modulo m
+    /// * `m` - The modulus, must be non-zero
+    pub fn congruent_to(mut self, r: u64, m: u64) -> Self {
+        self.congruence = (r, m);
+        self
+    }
+
+    /// Requires p to be a safe prime, with (p - 1) / 2 prime as well
+    pub fn safe(mut self) -> Self {
+        self.safe = true;
+        self
+    }
+
+    /// Requires p to be a Sophie Germain prime, with 2p + 1 prime as well
+    pub fn sophie_germain(mut self) -> Self {
+        self.sophie_germain = true;
+        self
+    }
+
+    /// Sets how many candidates are drawn before giving up
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Draws candidates from `rng` until one satisfies every constraint
+    ///
+    /// # Returns
+    ///
+    /// The first satisfying prime, which for a seeded generator is the same
+    /// on every run
+    ///
+    /// # Errors
+    ///
+    /// Returns a `GenError` if the constraints are malformed, if the bit length
+    /// and congruence leave no candidate, or if `max_attempts` draws all fail
+    pub fn generate<R: RandomSource>(&self, rng: &mut R) -> Result<u64, GenError> {
+        let (low, high): (u64, u64) = match self.bits {
+            None => (2, u64::MAX),
+            Some(bits @ 2..=64) => (1 << (bits - 1), u64::MAX >> (64 - bits)),
+            Some(bits) => return Err(GenError::InvalidBits(bits)),
+        };
+        let (r, m) = self.congruence;
+        if m == 0 {
+            return Err(GenError::ZeroModulus);
+        }
+
+        // Candidates are r + j × m for j in [first, last]
+        let (r, m) = (r % m, m as u128);
+        let first = (low as u128).saturating_sub(r as u128).div_ceil(m);
+        let last = match (high as u128).checked_sub(r as u128) {
+            Some(span) => span / m,
+            None => return Err(GenError::NoCandidates),
+        };
+        if first > last {
+            return Err(GenError::NoCandidates);
+        }
+
+        for _ in 0..self.max_attempts {
+            let j = first + uniform_below(rng, last - first + 1);
+            let candidate = (r as u128 + j * m) as u64;
+            if self.accepts(candidate) {
+                return Ok(candidate);
+            }
+        }
+
+        Err(GenError::Exhausted {
+            attempts: self.max_attempts,
+        })
+    }
+
+    /// Checks the primality constraints on a candidate of the right shape
+    fn accepts(&self, candidate: u64) -> bool {
+        is_prime_miller_rabin(candidate, 0)
+            && (!self.safe || is_safe_prime(candidate))
+            && (!self.sophie_germain || is_sophie_germain_prime(candidate))
+    }
+}
+
+impl Default for PrimeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt::Display;
+
+use num_traits::PrimInt;
+
+use super::PrimalityTest;
+
+/// Asserts that an algorithm reports n as prime
+///
+/// On failure, the panic message names the algorithm and states the expected
+/// and actual verdicts, e.g.
+/// `assertion failed: Sieve of Eratosthenes reported 9 as composite, expected prime`.
+///
+/// # Arguments
+///
+/// * `algo` - A value (not a reference) implementing `PrimalityTest<N>`; it is borrowed
+/// * `n` - The number to test
+#[macro_export]
+macro_rules! assert_prime {
+    ($algo:expr, $n:expr $(,)?) => {
+        $crate::algorithms::macros::assert_primality(&$algo, $n, true)
+    };
+}
+
+/// Asserts that an algorithm reports n as composite
+///
+/// The counterpart of `assert_prime!`, with the same failure message format.
+///
+/// # Arguments
+///
+/// * `algo` - A value (not a reference) implementing `PrimalityTest<N>`; it is borrowed
+/// * `n` - The number to test
+#[macro_export]
+macro_rules! assert_composite {
+    ($algo:expr, $n:expr $(,)?) => {
+        $crate::algorithms::macros::assert_primality(&$algo, $n, false)
+    };
+}
+
+/// Implementation of `assert_prime!` and `assert_composite!`
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_primality<N, A>(algo: &A, n: N, expected: bool)
+where
+    N: PrimInt + Display,
+    A: PrimalityTest<N> + ?Sized,
+{
+    let verdict = |prime: bool| if prime { "prime" } else { "composite" };
+
+    let actual = algo.is_prime(n);
+    if actual != expected {
+        panic!(
+            "assertion failed: {} reported {} as {}, expected {}",
+            algo.name(),
+            n,
+            verdict(actual),
+            verdict(expected)
+        );
+    }
+}
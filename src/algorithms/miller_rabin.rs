@@ -1,4 +1,7 @@
-use super::PrimalityTest;
+use super::arith::ModArith;
+use super::rng::{uniform_below, RandomSource};
+use super::sieve::odd_composite_flags;
+use super::{trivial_primality, AlgorithmInfo, Assessment, Certainty, Complexity, PrimalityTest};
 use num_traits::{PrimInt, ToPrimitive, FromPrimitive, Unsigned};
 
 /// Implementation of the Miller-Rabin primality test
@@ -15,10 +18,95 @@ use num_traits::{PrimInt, ToPrimitive, FromPrimitive, Unsigned};
 ///
 /// # Correctness
 ///
-/// For u64 integers, this implementation uses deterministic witnesses, making it
-/// 100% accurate. False positives are impossible with these witnesses.
-#[derive(Default)]
-pub struct MillerRabinAlgorithm;
+/// For u64 integers, the default `SORENSON_WEBSTER_BASES` are deterministic,
+/// making it 100% accurate with 7 rounds. Wider inputs fall back to
+/// `DETERMINISTIC_WITNESSES`, which stay deterministic up to 3.18 × 10^23.
+/// A custom witness set can be configured with `with_witnesses`, in which case
+/// the guarantee only holds as far as the chosen witnesses do.
+#[derive(Clone, Debug)]
+pub struct MillerRabinAlgorithm {
+    witnesses: Vec<u64>,
+    /// Whether bases ≥ n are reduced modulo n instead of being skipped
+    reduce_bases: bool,
+    /// Whether a Fermat test to base 2 runs before the witness loop
+    fermat_prefilter: bool,
+}
+
+impl MillerRabinAlgorithm {
+    /// Creates a Miller-Rabin test using the given witness bases
+    ///
+    /// # Arguments
+    ///
+    /// * `witnesses` - The bases tested for every input; bases ≥ n are skipped
+    pub fn with_witnesses(witnesses: Vec<u64>) -> Self {
+        MillerRabinAlgorithm {
+            witnesses,
+            reduce_bases: false,
+            fermat_prefilter: false,
+        }
+    }
+
+    /// Creates a Miller-Rabin test using the 7 `SORENSON_WEBSTER_BASES`
+    ///
+    /// Deterministic for every n < 2^64 with 7 rounds instead of 12. The bases
+    /// are large, so they are reduced modulo n rather than skipped when ≥ n,
+    /// which is the condition under which the set is proven. This is the default.
+    pub fn sorenson_webster() -> Self {
+        MillerRabinAlgorithm {
+            witnesses: SORENSON_WEBSTER_BASES.to_vec(),
+            reduce_bases: true,
+            fermat_prefilter: false,
+        }
+    }
+
+    /// Enables or disables a Fermat pre-filter to base 2
+    ///
+    /// When enabled, each odd n ≥ 5 is first checked for 2^(n-1) ≡ 1 (mod n)
+    /// with a single modular exponentiation. Failing that check proves n
+    /// composite at once; the rare composites passing it (the base-2 Fermat
+    /// pseudoprimes) and every prime go on to the full witness loop, so the
+    /// verdicts are unchanged. The filter pays off for witness sets that do
+    /// not start with base 2; the default set already rejects most composites
+    /// on its first base, so there the filter mostly adds an exponentiation
+    /// for primes.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the pre-filter runs
+    pub fn with_fermat_prefilter(mut self, enabled: bool) -> Self {
+        self.fermat_prefilter = enabled;
+        self
+    }
+
+    /// Returns the witness bases used by this instance
+    pub fn witnesses(&self) -> &[u64] {
+        &self.witnesses
+    }
+
+    /// Runs the optional Fermat pre-filter, then the witness loop
+    ///
+    /// The Sorenson-Webster bases are only proven for u64 inputs, so wider
+    /// inputs switch to `DETERMINISTIC_WITNESSES`, which remain deterministic
+    /// well beyond that.
+    fn test<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(&self, n: N) -> bool {
+        if self.fermat_prefilter && trivial_primality(n).is_none() {
+            let two = N::from_u64(2).unwrap();
+            if two.pow_mod(n - N::one(), n) != N::one() {
+                return false;
+            }
+        }
+        if self.reduce_bases && n.to_u64().is_none() {
+            return miller_rabin_with_witnesses(n, &DETERMINISTIC_WITNESSES, false);
+        }
+        miller_rabin_with_witnesses(n, &self.witnesses, self.reduce_bases)
+    }
+}
+
+impl Default for MillerRabinAlgorithm {
+    fn default() -> Self {
+        Self::sorenson_webster()
+    }
+}
 
 impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityTest<N> for MillerRabinAlgorithm {
     fn name(&self) -> &'static str {
@@ -26,10 +114,64 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityTest<N> for M
     }
 
     fn is_prime(&self, n: N) -> bool {
-        is_prime_miller_rabin(n, 20)
+        self.test(n)
+    }
+
+    fn estimate_cost(&self, n: N) -> u64 {
+        // Each witness costs one modular exponentiation: log n squarings,
+        // each of them an O(log² n) multiplication
+        let bits = (n.count_ones() + n.count_zeros() - n.leading_zeros()).max(1) as u64;
+        bits.pow(3) * self.witnesses.len().max(1) as u64
+    }
+
+    fn describe(&self) -> AlgorithmInfo {
+        AlgorithmInfo {
+            name: PrimalityTest::<N>::name(self),
+            complexity: Complexity::PolyLog,
+        }
+    }
+
+    fn assess(&self, n: N) -> Assessment {
+        let verdict = self.test(n);
+
+        // A failed witness is a certificate of compositeness; a pass is only a
+        // proof below the bound where the deterministic set is known to work
+        let below_bound = n.to_u128().is_some_and(|n| n < DETERMINISTIC_BOUND);
+        let proven = !verdict
+            || trivial_primality(n).is_some()
+            || (self.witnesses == DETERMINISTIC_WITNESSES && below_bound)
+            || (self.reduce_bases
+                && self.witnesses == SORENSON_WEBSTER_BASES
+                && (n.to_u64().is_some() || below_bound));
+
+        Assessment {
+            verdict,
+            certainty: if proven { Certainty::Proven } else { Certainty::Probable },
+        }
     }
 }
 
+/// Deterministic set of witnesses for all u64 numbers
+///
+/// Testing the first 12 primes as bases is enough to classify every
+/// n < 3.18 × 10^23 correctly, which covers the whole u64 range.
+pub const DETERMINISTIC_WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Exclusive bound below which `DETERMINISTIC_WITNESSES` never accept a composite
+///
+/// This is ψ₁₂, the smallest strong pseudoprime to all of the first 12 prime
+/// bases (Sorenson and Webster, 2015).
+const DETERMINISTIC_BOUND: u128 = 318_665_857_834_031_151_167_461;
+
+/// Seven bases that make Miller-Rabin deterministic for every n < 2^64
+///
+/// Found by Jim Sinclair (2011) in the search for minimal base sets that
+/// Sorenson and Webster later extended beyond 2^64. Unlike `DETERMINISTIC_WITNESSES` these bases
+/// are not small primes, so each one must be reduced modulo n, and a base
+/// that reduces to 0 is skipped. Use them through
+/// `MillerRabinAlgorithm::sorenson_webster` (also the default), which applies that rule.
+pub const SORENSON_WEBSTER_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
 /// Miller-Rabin primality test with deterministic witnesses
 ///
 /// This is a fast probabilistic primality test. For 64-bit integers,
@@ -56,40 +198,125 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityTest<N> for M
 ///
 /// See [Miller-Rabin Primality Test](https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test)
 pub fn is_prime_miller_rabin<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(n: N, _k: u32) -> bool {
-    let zero = N::zero();
-    let one = N::one();
-    let two = N::from_u64(2).unwrap();
-    let three = N::from_u64(3).unwrap();
-    
+    miller_rabin_with_witnesses(n, &DETERMINISTIC_WITNESSES, false)
+}
+
+/// Tests any integer convertible to u64 with deterministic Miller-Rabin
+///
+/// Accepts signed and pointer-sized types (`i32`, `usize`, ...) without manual
+/// casts. Values that cannot be converted are reported as not prime: this
+/// covers negative numbers, but also values above u64::MAX, which are not
+/// tested at all.
+///
+/// # Arguments
+///
+/// * `n` - The number to test
+///
+/// # Returns
+///
+/// `true` if n converts to a prime u64, `false` otherwise
+pub fn is_prime_any<T: TryInto<u64>>(n: T) -> bool {
+    n.try_into().is_ok_and(|n: u64| is_prime_miller_rabin(n, 0))
+}
+
+/// Runs the Miller-Rabin test with an explicit list of witness bases
+///
+/// With `reduce_bases` set, bases ≥ n are reduced modulo n (and skipped only if
+/// they reduce to 0) instead of being skipped outright.
+fn miller_rabin_with_witnesses<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(
+    n: N,
+    witnesses: &[u64],
+    reduce_bases: bool,
+) -> bool {
     // Handle small cases
-    if n <= one {
-        return false;
+    if let Some(result) = trivial_primality(n) {
+        return result;
     }
-    if n == two || n == three {
-        return true;
+
+    // Express n - 1 as 2^r * d where d is odd
+    let (d, r) = decompose(n);
+
+    // Test with each witness
+    //
+    // Invariant: from here on n is odd and n ≥ 5. Bases a ≥ n are skipped
+    // (this includes a == n, which would reduce to 0 and prove nothing). For
+    // the deterministic set this is always sound: bases 2 and 3 are below every
+    // such n, so at least one witness runs. Custom witness sets offer no such
+    // guarantee, so if every base was skipped we fall back to base 2 rather than
+    // report an untested n as prime. In `reduce_bases` mode a base is replaced
+    // by a mod n first, and only skipped if that is 0.
+    let mut tested = false;
+    for a_n in applicable_bases(n, witnesses, reduce_bases) {
+        tested = true;
+        if !check_composite(a_n, d, r, n) {
+            return false;
+        }
     }
-    if n % two == zero {
-        return false;
+
+    if !tested {
+        return check_composite(N::from_u64(2).unwrap(), d, r, n);
     }
 
-    // Express n - 1 as 2^r * d where d is odd
-    let mut d = n - one;
-    let mut r = 0u32;
-    while d % two == zero {
-        d = d / two;
-        r += 1;
+    true
+}
+
+/// Yields the bases of `witnesses` that `miller_rabin_with_witnesses` runs on n
+///
+/// Bases a ≥ n and a == 0 are skipped; with `reduce_bases` each base is
+/// replaced by a mod n first.
+pub(crate) fn applicable_bases<'a, N: PrimInt + ToPrimitive + FromPrimitive + 'a>(
+    n: N,
+    witnesses: &'a [u64],
+    reduce_bases: bool,
+) -> impl Iterator<Item = N> + 'a {
+    witnesses.iter().filter_map(move |&a| {
+        let a = match n.to_u64() {
+            Some(n_u64) if reduce_bases => a % n_u64,
+            _ => a,
+        };
+        N::from_u64(a).filter(|&a_n| a_n < n && a != 0)
+    })
+}
+
+/// Miller-Rabin test with extra random witnesses on top of the deterministic set
+///
+/// Runs the deterministic witnesses of `is_prime_miller_rabin` first, then
+/// `extra_random` additional bases drawn uniformly from [2, n - 2]. The extra
+/// rounds add nothing for u64 inputs, but increase confidence for inputs beyond
+/// the range where the deterministic witnesses are proven (e.g. large u128 values).
+/// Each base takes one random value, or two when n - 3 exceeds 2^64.
+///
+/// # Arguments
+///
+/// * `n` - The number to test for primality
+/// * `extra_random` - Number of additional random bases to test
+/// * `rng` - Source of randomness used to pick the extra bases
+///
+/// # Returns
+///
+/// `true` if n passes every witness (prime), `false` if n is composite
+pub fn is_prime_miller_rabin_extra<N, R>(n: N, extra_random: u32, rng: &mut R) -> bool
+where
+    N: PrimInt + ToPrimitive + FromPrimitive + Unsigned,
+    R: RandomSource,
+{
+    if !is_prime_miller_rabin(n, 0) {
+        return false;
+    }
+    if let Some(result) = trivial_primality(n) {
+        return result;
     }
 
-    // Deterministic set of witnesses for all u64 numbers
-    let witnesses = [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    let two = N::from_u64(2).unwrap();
+    let three = N::from_u64(3).unwrap();
+    let (d, r) = decompose(n);
+
+    // Bases are picked in [2, n - 2], so there are n - 3 candidates
+    let span = (n - three).to_u128().unwrap();
 
-    // Test with each witness
-    for &a in &witnesses {
-        let a_n = N::from_u64(a).unwrap();
-        if a_n >= n {
-            continue;
-        }
-        if !check_composite(a_n, d, r, n) {
+    for _ in 0..extra_random {
+        let a = two + N::from_u128(uniform_below(rng, span)).unwrap();
+        if !check_composite(a, d, r, n) {
             return false;
         }
     }
@@ -97,20 +324,94 @@ pub fn is_prime_miller_rabin<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned
     true
 }
 
+/// Strong probable prime test to a single base
+///
+/// This is one round of Miller-Rabin: n passes if a^d ≡ 1 (mod n) or
+/// a^(2^s × d) ≡ -1 (mod n) for some 0 ≤ s < r, where n - 1 = 2^r × d.
+///
+/// # Arguments
+///
+/// * `n` - The odd number to test, at least 3
+/// * `base` - The witness base; it is reduced modulo n first
+///
+/// # Returns
+///
+/// `true` if n is a strong probable prime to the given base, `false` if the
+/// base proves n composite (including when base ≡ 0 mod n, which proves nothing
+/// and is therefore reported as a failure to pass)
+pub fn is_strong_probable_prime(n: u64, base: u64) -> bool {
+    if n < 3 || n.is_multiple_of(2) {
+        return n == 2;
+    }
+
+    let a = base % n;
+    if a == 0 {
+        return false;
+    }
+
+    let (d, r) = decompose(n);
+    check_composite(a, d, r, n)
+}
+
+/// Counts the strong pseudoprimes to a single base up to `limit`
+///
+/// A strong pseudoprime is an odd composite number which nevertheless passes
+/// the strong probable prime test for the base. The fewer pseudoprimes a base
+/// has, the "stronger" it is as a Miller-Rabin witness.
+///
+/// # Arguments
+///
+/// * `base` - The witness base to analyze
+/// * `limit` - Inclusive upper bound of the composites considered
+///
+/// # Returns
+///
+/// The number of odd composites n ≤ limit that the base fails to detect
+pub fn base_strength(base: u64, limit: u64) -> usize {
+    if limit < 9 {
+        return 0;
+    }
+
+    let flags = odd_composite_flags(limit);
+    flags
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|&(_, &composite)| composite)
+        .map(|(i, _)| 2 * i as u64 + 1)
+        .filter(|&n| is_strong_probable_prime(n, base))
+        .count()
+}
+
+/// Writes n - 1 as 2^r × d with d odd, returning (d, r)
+fn decompose<N: PrimInt + FromPrimitive>(n: N) -> (N, u32) {
+    let zero = N::zero();
+    let two = N::from_u64(2).unwrap();
+
+    let mut d = n - N::one();
+    let mut r = 0u32;
+    while d % two == zero {
+        d = d / two;
+        r += 1;
+    }
+
+    (d, r)
+}
+
 /// Checks if witness `a` proves that `n` is composite
 ///
 /// Returns `true` if `n` passes the test with witness `a` (likely prime).
 /// Returns `false` if `n` is definitely composite.
-fn check_composite<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(a: N, d: N, r: u32, n: N) -> bool {
+fn check_composite<N: ModArith>(a: N, d: N, r: u32, n: N) -> bool {
     let one = N::one();
-    let mut x = pow_mod(a, d, n);
+    let mut x = a.pow_mod(d, n);
 
     if x == one || x == n - one {
         return true;
     }
 
     for _ in 0..r - 1 {
-        x = mul_mod(x, x, n);
+        x = x.mul_mod(x, n);
         if x == n - one {
             return true;
         }
@@ -118,33 +419,3 @@ fn check_composite<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(a: N, d:
 
     false
 }
-
-/// Computes (a × b) mod n using u128 to prevent overflow
-fn mul_mod<N: PrimInt + ToPrimitive + FromPrimitive>(a: N, b: N, n: N) -> N {
-    let a_u128 = a.to_u128().unwrap();
-    let b_u128 = b.to_u128().unwrap();
-    let n_u128 = n.to_u128().unwrap();
-    
-    let result = (a_u128 * b_u128) % n_u128;
-    N::from_u128(result).unwrap()
-}
-
-/// Computes base^exp mod modulo using binary exponentiation
-fn pow_mod<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(mut base: N, mut exp: N, modulo: N) -> N {
-    let zero = N::zero();
-    let one = N::one();
-    let two = N::from_u64(2).unwrap();
-    
-    let mut result = one;
-    base = base % modulo;
-
-    while exp > zero {
-        if exp % two == one {
-            result = mul_mod(result, base, modulo);
-        }
-        exp = exp >> 1;
-        base = mul_mod(base, base, modulo);
-    }
-
-    result
-}
@@ -1,5 +1,14 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
 use num_traits::{PrimInt, ToPrimitive, FromPrimitive, Unsigned};
 
+use arith::inverse_mod_2_64;
+use rng::{RandomSource, SplitMix64};
+
 /// Sieve of Eratosthenes primality test
 pub mod sieve;
 
@@ -9,6 +18,49 @@ pub mod miller_rabin;
 /// Riemann Hypothesis-based primality test
 pub mod zeta;
 
+/// Baillie-PSW probable prime test and its component tests
+pub mod bpsw;
+
+/// Overflow-safe modular arithmetic
+pub mod arith;
+
+/// Integer factorization
+pub mod factor;
+
+/// Fallback chains combining several primality tests
+pub mod chain;
+
+/// Primality tests for numbers of special form
+pub mod special_forms;
+
+/// Prime-counting function and its analytic approximations
+pub mod counting;
+
+/// Prime generation and iteration
+pub mod primes;
+
+/// Seedable random number generation for randomized algorithms
+pub mod rng;
+
+/// Curated primality test vectors shared by tests and benchmarks
+pub mod vectors;
+
+/// Randomized cross-checking of the registered algorithms
+pub mod fuzz;
+
+/// Parsing of numbers supplied as text or bytes
+pub mod parse;
+
+/// Random primes generated under constraints
+pub mod generate;
+
+/// Machine-readable timings of the registered algorithms
+pub mod suite;
+
+/// Assertion macros for testing primality algorithms
+#[doc(hidden)]
+pub mod macros;
+
 /// Centralized tests for all algorithms
 #[cfg(test)]
 mod tests;
@@ -41,6 +93,384 @@ pub trait PrimalityTest<N: PrimInt> {
     /// For probabilistic algorithms, false negatives (saying a prime is composite)
     /// are impossible, but false positives are extremely unlikely with good witnesses.
     fn is_prime(&self, n: N) -> bool;
+
+    /// Tests n for primality, drawing any randomness from `rng`
+    ///
+    /// The default implementation ignores `rng` and calls `is_prime`, which is
+    /// right for deterministic algorithms. Randomized algorithms should
+    /// override it to take every random choice from `rng`, so that
+    /// `PrimalityRegistry::compare_with_config` runs with the same seed give
+    /// the same results.
+    fn is_prime_seeded(&self, n: N, _rng: &mut dyn RandomSource) -> bool {
+        self.is_prime(n)
+    }
+
+    /// Estimates the relative cost of testing `n` without running the test
+    ///
+    /// The unit is roughly "one modular operation", so estimates can be
+    /// compared across algorithms, e.g. by a scheduler choosing which
+    /// algorithm to run. The default implementation returns `u64::MAX`,
+    /// meaning the cost is unknown.
+    fn estimate_cost(&self, _n: N) -> u64 {
+        u64::MAX
+    }
+
+    /// Describes the algorithm for programmatic inspection
+    ///
+    /// The default implementation reports the algorithm name with an
+    /// `Unknown` complexity class. Override it to let tools sort or filter
+    /// your algorithm by its asymptotic cost.
+    fn describe(&self) -> AlgorithmInfo {
+        AlgorithmInfo {
+            name: self.name(),
+            complexity: Complexity::Unknown,
+        }
+    }
+
+    /// Tests n and reports how far the verdict can be trusted
+    ///
+    /// This is `is_prime` with a certainty attached, so callers can tell a
+    /// proof from a probable answer without changing how `is_prime` behaves.
+    /// The default implementation reports `Probable`, since nothing is known
+    /// about the algorithm; override it when the verdict is proven.
+    fn assess(&self, n: N) -> Assessment {
+        Assessment {
+            verdict: self.is_prime(n),
+            certainty: Certainty::Probable,
+        }
+    }
+
+    /// Largest input the algorithm handles reliably and in reasonable time
+    ///
+    /// The default implementation returns `u64::MAX`, meaning there is no
+    /// limit. Algorithms whose running time or accuracy degrades for large
+    /// inputs should override it so that `try_is_prime` can refuse them.
+    fn max_reliable(&self) -> u64 {
+        u64::MAX
+    }
+
+    /// Tests n for primality, refusing inputs above `max_reliable`
+    ///
+    /// # Errors
+    ///
+    /// Returns `PrimalityError::OutOfRange` if n exceeds `max_reliable`
+    /// (including values that do not fit in a u64 when the algorithm has a limit)
+    fn try_is_prime(&self, n: N) -> Result<bool, PrimalityError> {
+        let max_reliable = self.max_reliable();
+        if max_reliable != u64::MAX && n.to_u64().is_none_or(|n| n > max_reliable) {
+            return Err(PrimalityError::OutOfRange { max_reliable });
+        }
+        Ok(self.is_prime(n))
+    }
+}
+
+/// Errors reported by the fallible primality test methods
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrimalityError {
+    /// The input exceeds the largest value the algorithm handles reliably
+    OutOfRange {
+        /// The algorithm's `max_reliable` bound
+        max_reliable: u64,
+    },
+    /// The input does not fit in the widest integer type supported
+    TooWide {
+        /// Number of bits the widest supported type holds
+        max_bits: u32,
+    },
+}
+
+impl fmt::Display for PrimalityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimalityError::OutOfRange { max_reliable } => write!(
+                f,
+                "input exceeds the largest value the algorithm handles reliably ({})",
+                max_reliable
+            ),
+            PrimalityError::TooWide { max_bits } => write!(f, "input does not fit in {} bits", max_bits),
+        }
+    }
+}
+
+impl std::error::Error for PrimalityError {}
+
+/// How far a primality verdict can be trusted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Certainty {
+    /// The verdict is mathematically certain
+    Proven,
+    /// The verdict holds with overwhelming probability but is not proven
+    Probable,
+    /// The verdict rests on a heuristic with no error bound
+    HeuristicGuess,
+}
+
+/// Primality verdict together with its certainty, as returned by `assess`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Assessment {
+    /// `true` if the number was judged prime
+    pub verdict: bool,
+    /// How far the verdict can be trusted
+    pub certainty: Certainty,
+}
+
+/// Asymptotic time complexity class of a primality test
+///
+/// Variants are ordered from cheapest to most expensive for large inputs,
+/// so sorting by `Complexity` ranks algorithms by how well they scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Complexity {
+    /// Polynomial in the number of digits, e.g. O(k log³n)
+    PolyLog,
+    /// Proportional to the square root of n, e.g. trial division
+    Sqrt,
+    /// Guided by a heuristic whose cost has no clean closed form
+    Heuristic,
+    /// Not reported by the algorithm
+    Unknown,
+}
+
+/// Structured description of a primality testing algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlgorithmInfo {
+    /// The algorithm name, as returned by `PrimalityTest::name`
+    pub name: &'static str,
+    /// The asymptotic complexity class
+    pub complexity: Complexity,
+}
+
+/// The algorithms registered by `PrimalityRegistry::with_all_algorithms`
+///
+/// An enum-based alternative to selecting algorithms by name. `Display`
+/// prints the registry name and `TryFrom<&str>` parses it back, so every
+/// variant round-trips through `PrimalityRegistry::get_by_name`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// `sieve::SieveAlgorithm`
+    Sieve,
+    /// `miller_rabin::MillerRabinAlgorithm`
+    MillerRabin,
+    /// `zeta::ZetaAlgorithm`
+    Zeta,
+}
+
+impl Algorithm {
+    /// Returns every variant, in registration order
+    pub fn all() -> [Algorithm; 3] {
+        [Algorithm::Sieve, Algorithm::MillerRabin, Algorithm::Zeta]
+    }
+
+    /// Returns the name the algorithm is registered under
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Sieve => "Sieve of Eratosthenes",
+            Algorithm::MillerRabin => "Miller-Rabin",
+            Algorithm::Zeta => "Riemann Zeta",
+        }
+    }
+
+    /// Creates the algorithm with its default configuration
+    pub fn build<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(self) -> Box<dyn PrimalityTest<N>> {
+        match self {
+            Algorithm::Sieve => Box::new(sieve::SieveAlgorithm::default()),
+            Algorithm::MillerRabin => Box::new(miller_rabin::MillerRabinAlgorithm::default()),
+            Algorithm::Zeta => Box::new(zeta::ZetaAlgorithm),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl TryFrom<&str> for Algorithm {
+    type Error = UnknownAlgorithmError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Algorithm::all()
+            .into_iter()
+            .find(|algo| algo.name() == name)
+            .ok_or_else(|| UnknownAlgorithmError { name: name.to_string() })
+    }
+}
+
+/// Error returned when a name matches no `Algorithm` variant
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownAlgorithmError {
+    /// The name that was looked up
+    pub name: String,
+}
+
+impl fmt::Display for UnknownAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown algorithm name: {:?}", self.name)
+    }
+}
+
+impl std::error::Error for UnknownAlgorithmError {}
+
+/// Settings of a reproducible `PrimalityRegistry::compare_with_config` run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComparisonConfig {
+    /// Seed of the random source handed to every algorithm; each algorithm
+    /// and the oracle get their own generator started from it
+    pub seed: u64,
+    /// Time budget per algorithm over the whole range, or `None` for no limit
+    ///
+    /// An algorithm that exceeds it stops early and is reported as timed out.
+    /// Where the cut falls depends on the machine, so only runs that do not
+    /// time out are reproducible.
+    pub timeout: Option<Duration>,
+    /// The algorithm whose answers are taken as ground truth
+    pub oracle: Algorithm,
+}
+
+impl Default for ComparisonConfig {
+    /// Seed 0, no timeout and Miller-Rabin, which is exact for u64, as oracle
+    fn default() -> Self {
+        ComparisonConfig {
+            seed: 0,
+            timeout: None,
+            oracle: Algorithm::MillerRabin,
+        }
+    }
+}
+
+/// One algorithm's results in a `ComparisonReport`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComparisonRow {
+    /// The algorithm compared with the oracle
+    pub algorithm: AlgorithmId,
+    /// Number of inputs tested before the range ended or the budget ran out
+    pub checked: usize,
+    /// Number of tested inputs on which it disagrees with the oracle
+    pub disagreements: usize,
+    /// Whether the time budget ran out before the end of the range
+    pub timed_out: bool,
+}
+
+/// Outcome of `PrimalityRegistry::compare_with_config`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComparisonReport {
+    /// The configuration the report was produced with
+    pub config: ComparisonConfig,
+    /// One row per registered algorithm, in registration order
+    pub rows: Vec<ComparisonRow>,
+}
+
+/// Identifier of a registered algorithm, wrapping its `PrimalityTest::name`
+///
+/// Keys maps of per-algorithm results, such as the one returned by
+/// `PrimalityRegistry::results_map`, so that algorithm identifiers cannot be
+/// mixed up with arbitrary strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AlgorithmId(pub &'static str);
+
+impl fmt::Display for AlgorithmId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Adapts a plain function into a u64 `PrimalityTest`
+///
+/// Lets an experimental test be registered and compared against the
+/// built-in algorithms without defining a struct for it. Non-capturing
+/// closures coerce to `fn(u64) -> bool`, so
+/// `FnAlgorithm::new("odd", |n| n % 2 == 1)` works as well as a named
+/// function. The function is called as-is, without the trivial cases of
+/// `trivial_primality` being handled first.
+#[derive(Clone, Copy, Debug)]
+pub struct FnAlgorithm {
+    /// Name reported by `PrimalityTest::name`
+    pub name: &'static str,
+    /// The primality test itself
+    pub f: fn(u64) -> bool,
+}
+
+impl FnAlgorithm {
+    /// Wraps `f` under the registry name `name`
+    pub const fn new(name: &'static str, f: fn(u64) -> bool) -> Self {
+        FnAlgorithm { name, f }
+    }
+}
+
+impl PrimalityTest<u64> for FnAlgorithm {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_prime(&self, n: u64) -> bool {
+        (self.f)(n)
+    }
+}
+
+/// Resolves the trivial primality cases shared by every algorithm
+///
+/// Handles n < 2, n == 2, n == 3 and even n so that each algorithm does not
+/// have to re-implement these checks (slightly differently) on its own.
+///
+/// # Arguments
+///
+/// * `n` - The integer to classify
+///
+/// # Returns
+///
+/// `Some(result)` if the primality of n is settled by one of the trivial cases,
+/// `None` if n is odd and at least 5, meaning the caller must run its actual test
+pub fn trivial_primality<N: PrimInt + FromPrimitive>(n: N) -> Option<bool> {
+    let one = N::one();
+    let two = N::from_u64(2).unwrap();
+    let three = N::from_u64(3).unwrap();
+
+    if n <= one {
+        return Some(false);
+    }
+    if n == two || n == three {
+        return Some(true);
+    }
+    if n % two == N::zero() {
+        return Some(false);
+    }
+
+    None
+}
+
+/// Odd primes screened by `quick_composite_check`, with their inverses modulo 2^64
+const QUICK_SCREEN: [(u64, u64); 5] = [
+    (3, inverse_mod_2_64(3)),
+    (5, inverse_mod_2_64(5)),
+    (7, inverse_mod_2_64(7)),
+    (11, inverse_mod_2_64(11)),
+    (13, inverse_mod_2_64(13)),
+];
+
+/// Settles n at once if it is divisible by one of 2, 3, 5, 7, 11 or 13
+///
+/// A cheap front-end to run before any full primality test: about 81% of
+/// integers have one of these factors. Evenness is read from the low bit and
+/// every odd prime p is tested without a division: p divides n exactly when
+/// n × p⁻¹ mod 2^64 is at most ⌊(2^64 - 1) / p⌋.
+///
+/// # Returns
+///
+/// `Some(false)` if n < 2 or n is a proper multiple of one of the screened
+/// primes, `Some(true)` if n is one of them, and `None` if n has none of them
+/// as a factor, meaning a full test is needed
+pub fn quick_composite_check(n: u64) -> Option<bool> {
+    if n < 2 {
+        return Some(false);
+    }
+    if n & 1 == 0 {
+        return Some(n == 2);
+    }
+
+    QUICK_SCREEN
+        .iter()
+        .find(|&&(p, inverse)| n.wrapping_mul(inverse) <= u64::MAX / p)
+        .map(|&(p, _)| n == p)
 }
 
 /// Registry for managing and comparing primality testing algorithms
@@ -49,16 +479,27 @@ pub trait PrimalityTest<N: PrimInt> {
 /// convenient methods for registering new algorithms and accessing them by name.
 pub struct PrimalityRegistry<N: PrimInt> {
     algorithms: Vec<Box<dyn PrimalityTest<N>>>,
+    /// Index of the fastest correct algorithm per number of decimal digits,
+    /// filled in by `fastest_correct`
+    fastest: RefCell<HashMap<u32, usize>>,
 }
 
 impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityRegistry<N> {
-    /// Creates a new empty registry
+    /// Creates a new registry with all available algorithms
     ///
-    /// Use this if you want to manually register specific algorithms.
-    /// To get all registered algorithms at once, use `with_all_algorithms()`.
+    /// Same as `with_all_algorithms()` and `Default`. Use `empty()` if you
+    /// want to manually register specific algorithms.
     pub fn new() -> Self {
+        Self::with_all_algorithms()
+    }
+
+    /// Creates a registry with no algorithms
+    ///
+    /// Use this if you want to manually register specific algorithms.
+    pub fn empty() -> Self {
         PrimalityRegistry {
             algorithms: Vec::new(),
+            fastest: RefCell::new(HashMap::new()),
         }
     }
 
@@ -67,12 +508,12 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityRegistry<N> {
     /// This is the recommended way to create a registry if you want
     /// to use all implemented algorithms.
     pub fn with_all_algorithms() -> Self {
-        let mut registry = PrimalityRegistry::new();
+        let mut registry = PrimalityRegistry::empty();
 
         // Register all algorithms here - add new ones as you create them
         registry.register(sieve::SieveAlgorithm::default());
         registry.register(miller_rabin::MillerRabinAlgorithm::default());
-        registry.register(zeta::ZetaAlgorithm::default());
+        registry.register(zeta::ZetaAlgorithm);
 
         registry
     }
@@ -84,6 +525,8 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityRegistry<N> {
     /// * `algo` - The algorithm to register, must implement `PrimalityTest`
     pub fn register<T: PrimalityTest<N> + 'static>(&mut self, algo: T) {
         self.algorithms.push(Box::new(algo));
+        // A newcomer may beat the cached choices
+        self.fastest.get_mut().clear();
     }
 
     /// Returns a slice of all registered algorithms
@@ -91,6 +534,16 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityRegistry<N> {
         &self.algorithms
     }
 
+    /// Returns the number of registered algorithms
+    pub fn len(&self) -> usize {
+        self.algorithms.len()
+    }
+
+    /// Returns `true` if no algorithm is registered
+    pub fn is_empty(&self) -> bool {
+        self.algorithms.is_empty()
+    }
+
     /// Finds an algorithm by its name
     ///
     /// # Arguments
@@ -100,9 +553,168 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityRegistry<N> {
     /// # Returns
     ///
     /// Some reference to the algorithm if found, None otherwise
+    #[allow(clippy::borrowed_box)]
     pub fn get_by_name(&self, name: &str) -> Option<&Box<dyn PrimalityTest<N>>> {
         self.algorithms.iter().find(|a| a.name() == name)
     }
+
+    /// Tests n with the fastest registered algorithm for its magnitude
+    ///
+    /// Inputs are bucketed by their number of decimal digits. The first call
+    /// in a bucket times every registered algorithm on n, discards those that
+    /// refuse n or disagree with the deterministic Miller-Rabin oracle, and
+    /// caches the fastest of the rest; later calls in the same bucket run the
+    /// cached algorithm only. Registering an algorithm clears the cache.
+    ///
+    /// The choice rests on a single timing, so warm each bucket with a
+    /// representative input: an even number is settled instantly by every
+    /// algorithm and says little about which one scales best.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the algorithm used and its verdict on n, or `None`
+    /// if no registered algorithm handles n correctly
+    pub fn fastest_correct(&self, n: N) -> Option<(AlgorithmId, bool)> {
+        let bucket = n.to_u128().and_then(|n| n.checked_ilog10()).unwrap_or(0);
+
+        let cached = self.fastest.borrow().get(&bucket).copied();
+        let index = match cached {
+            Some(index) => index,
+            None => {
+                let expected = miller_rabin::is_prime_miller_rabin(n, 0);
+                let index = self
+                    .algorithms
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, algo)| {
+                        let start = Instant::now();
+                        let verdict = algo.try_is_prime(n).ok()?;
+                        (verdict == expected).then(|| (start.elapsed(), i))
+                    })
+                    .min()?
+                    .1;
+                self.fastest.borrow_mut().insert(bucket, index);
+                index
+            }
+        };
+
+        let algo = &self.algorithms[index];
+        Some((AlgorithmId(algo.name()), algo.is_prime(n)))
+    }
+
+    /// Tests n with every registered algorithm
+    ///
+    /// # Returns
+    ///
+    /// Each algorithm's verdict on n, keyed by its identifier
+    pub fn results_map(&self, n: N) -> HashMap<AlgorithmId, bool> {
+        self.algorithms
+            .iter()
+            .map(|a| (AlgorithmId(a.name()), a.is_prime(n)))
+            .collect()
+    }
+
+    /// Scores every registered algorithm against a trusted oracle
+    ///
+    /// # Arguments
+    ///
+    /// * `oracle` - The algorithm whose answers are taken as ground truth
+    /// * `range` - The inputs to test, bounds included
+    ///
+    /// # Returns
+    ///
+    /// For each registered algorithm, in registration order, its name and the
+    /// number of inputs in the range on which it disagrees with the oracle
+    pub fn compare_accuracy(
+        &self,
+        oracle: &dyn PrimalityTest<N>,
+        range: RangeInclusive<N>,
+    ) -> Vec<(&'static str, usize)> {
+        let (start, end) = range.into_inner();
+        let mut disagreements = vec![0usize; self.algorithms.len()];
+
+        let mut n = start;
+        while n <= end {
+            let expected = oracle.is_prime(n);
+            for (count, algo) in disagreements.iter_mut().zip(&self.algorithms) {
+                if algo.is_prime(n) != expected {
+                    *count += 1;
+                }
+            }
+
+            if n == end {
+                break;
+            }
+            n = n + N::one();
+        }
+
+        self.algorithms
+            .iter()
+            .map(|a| a.name())
+            .zip(disagreements)
+            .collect()
+    }
+
+    /// Scores every registered algorithm against an oracle, reproducibly
+    ///
+    /// Like `compare_accuracy`, with the oracle and the random source given
+    /// by `config`. Every algorithm, and the oracle, tests the whole range
+    /// through `PrimalityTest::is_prime_seeded` with its own generator seeded
+    /// from `config.seed`, so randomized algorithms make the same choices on
+    /// every run and the report only depends on the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Seed, per-algorithm time budget and oracle
+    /// * `range` - The inputs to test, bounds included
+    ///
+    /// # Returns
+    ///
+    /// A `ComparisonReport` with one row per registered algorithm
+    pub fn compare_with_config(&self, config: &ComparisonConfig, range: RangeInclusive<N>) -> ComparisonReport {
+        let (start, end) = range.into_inner();
+        let inputs = || {
+            std::iter::successors((start <= end).then_some(start), move |&n| {
+                (n < end).then(|| n + N::one())
+            })
+        };
+
+        let oracle = config.oracle.build::<N>();
+        let mut oracle_rng = SplitMix64::new(config.seed);
+        let expected: Vec<bool> = inputs().map(|n| oracle.is_prime_seeded(n, &mut oracle_rng)).collect();
+
+        let rows = self
+            .algorithms
+            .iter()
+            .map(|algo| {
+                let mut rng = SplitMix64::new(config.seed);
+                let started = Instant::now();
+                let mut row = ComparisonRow {
+                    algorithm: AlgorithmId(algo.name()),
+                    checked: 0,
+                    disagreements: 0,
+                    timed_out: false,
+                };
+
+                for (n, &expected) in inputs().zip(&expected) {
+                    if config.timeout.is_some_and(|budget| started.elapsed() > budget) {
+                        row.timed_out = true;
+                        break;
+                    }
+                    row.checked += 1;
+                    if algo.is_prime_seeded(n, &mut rng) != expected {
+                        row.disagreements += 1;
+                    }
+                }
+                row
+            })
+            .collect();
+
+        ComparisonReport {
+            config: config.clone(),
+            rows,
+        }
+    }
 }
 
 impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> Default for PrimalityRegistry<N> {
@@ -110,3 +722,33 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> Default for PrimalityR
         Self::new()
     }
 }
+
+impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> FromIterator<Box<dyn PrimalityTest<N>>>
+    for PrimalityRegistry<N>
+{
+    fn from_iter<I: IntoIterator<Item = Box<dyn PrimalityTest<N>>>>(iter: I) -> Self {
+        PrimalityRegistry {
+            algorithms: iter.into_iter().collect(),
+            fastest: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<N: PrimInt> IntoIterator for PrimalityRegistry<N> {
+    type Item = Box<dyn PrimalityTest<N>>;
+    type IntoIter = std::vec::IntoIter<Box<dyn PrimalityTest<N>>>;
+
+    /// Consumes the registry, yielding its algorithms in registration order
+    fn into_iter(self) -> Self::IntoIter {
+        self.algorithms.into_iter()
+    }
+}
+
+impl<N: PrimInt> fmt::Debug for PrimalityRegistry<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.algorithms.iter().map(|a| a.name()).collect();
+        f.debug_struct("PrimalityRegistry")
+            .field("algorithms", &names)
+            .finish()
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+
+use super::miller_rabin::is_prime_miller_rabin;
+use super::PrimalityError;
+
+/// Errors reported by `is_prime_str` for malformed input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input holds no digits once whitespace is trimmed
+    Empty,
+    /// The input contains something other than digits and `_` separators
+    InvalidCharacter {
+        /// The offending character
+        character: char,
+        /// Its byte offset in the trimmed input
+        position: usize,
+    },
+    /// The number does not fit in a u128
+    Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => f.write_str("no digits to parse"),
+            ParseError::InvalidCharacter { character, position } => {
+                write!(f, "invalid character {:?} at position {}", character, position)
+            }
+            ParseError::Overflow => f.write_str("number does not fit in a u128"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a decimal number written the way Rust source writes it
+///
+/// Surrounding whitespace is ignored and `_` separators are accepted after
+/// the first digit, as in `1_000_003`.
+///
+/// # Errors
+///
+/// Returns a `ParseError` for empty input, for any other character
+/// (including a leading `_`, signs and `,` separators) and for values above
+/// u128::MAX
+pub fn parse_number(input: &str) -> Result<u128, ParseError> {
+    let trimmed = input.trim();
+    let mut value: Option<u128> = None;
+
+    for (position, character) in trimmed.char_indices() {
+        match (character, value) {
+            ('_', Some(_)) => {}
+            ('0'..='9', _) => {
+                let digit = character as u128 - '0' as u128;
+                let shifted = value.unwrap_or(0).checked_mul(10).and_then(|v| v.checked_add(digit));
+                value = Some(shifted.ok_or(ParseError::Overflow)?);
+            }
+            _ => return Err(ParseError::InvalidCharacter { character, position }),
+        }
+    }
+
+    value.ok_or(ParseError::Empty)
+}
+
+/// Tests a number given as a string for primality
+///
+/// The string is parsed with `parse_number`, so `" 1_000_003 "` is accepted,
+/// and the value is tested with the deterministic Miller-Rabin witnesses.
+///
+/// # Returns
+///
+/// `Ok(true)` if the number is prime, `Ok(false)` if it is not
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the string is not a valid number
+pub fn is_prime_str(input: &str) -> Result<bool, ParseError> {
+    parse_number(input).map(|n| is_prime_miller_rabin(n, 0))
+}
+
+/// Tests a big-endian unsigned integer given as bytes for primality
+///
+/// Leading zero bytes are ignored, so the same number may be sent with any
+/// amount of padding, and an empty slice reads as 0. The value is tested with
+/// the deterministic Miller-Rabin witnesses. Only values that fit in a u128
+/// are accepted for now; a wider backend can lift the limit without changing
+/// the signature.
+///
+/// # Returns
+///
+/// `Ok(true)` if the number is prime, `Ok(false)` if it is not
+///
+/// # Errors
+///
+/// Returns `PrimalityError::TooWide` if the number needs more than 128 bits
+pub fn is_prime_bytes_be(bytes: &[u8]) -> Result<bool, PrimalityError> {
+    let significant = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+    if significant.len() > 16 {
+        return Err(PrimalityError::TooWide { max_bits: u128::BITS });
+    }
+
+    let n = significant
+        .iter()
+        .fold(0u128, |value, &byte| (value << 8) | byte as u128);
+    Ok(is_prime_miller_rabin(n, 0))
+}
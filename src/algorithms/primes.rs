@@ -0,0 +1,451 @@
+use std::collections::VecDeque;
+use std::ops::{Range, RangeInclusive};
+
+use super::miller_rabin::is_prime_miller_rabin;
+use super::sieve::{primes_up_to, sieve_segment, SEGMENT_SIZE};
+
+/// Largest base prime `PrimeIterator` sieves with
+///
+/// Sieving a segment near n needs every prime up to √n; beyond 2^44, where
+/// that would exceed this bound, segments are filled by testing each number
+/// with Miller-Rabin instead.
+const MAX_BASE_PRIME: u64 = 1 << 22;
+
+/// Largest prime representable in a u64, 2^64 - 59
+pub const LARGEST_U64_PRIME: u64 = 18_446_744_073_709_551_557;
+
+/// Unbounded iterator over the primes in ascending order
+///
+/// Primes are produced with a segmented Sieve of Eratosthenes: the numbers are
+/// sieved one fixed-size segment at a time, and the base primes needed to sieve
+/// a segment are extended as the iteration moves upward. Memory use therefore
+/// grows only with √p, not with p.
+///
+/// The iteration ends after `LARGEST_U64_PRIME`: no prime lies between it and
+/// u64::MAX, and nothing past u64::MAX is ever examined.
+pub struct PrimeIterator {
+    /// Primes found in the current segment
+    segment: Vec<u64>,
+    /// Index of the next prime to yield from `segment`
+    position: usize,
+    /// Start of the next segment to sieve
+    low: u64,
+    /// All primes up to at least √high of the current segment
+    base_primes: Vec<u64>,
+    /// Whether the segments have reached u64::MAX
+    exhausted: bool,
+}
+
+impl PrimeIterator {
+    /// Creates an iterator starting at the first prime, 2
+    pub fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    /// Creates an iterator starting at the first prime ≥ `start`
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Lower bound of the primes to yield, included
+    pub fn starting_at(start: u64) -> Self {
+        PrimeIterator {
+            segment: Vec::new(),
+            position: 0,
+            low: start,
+            base_primes: Vec::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Sieves the next segment and stores its primes
+    fn refill(&mut self) {
+        let low = self.low;
+        // The last segment stops short of u64::MAX, which is composite
+        let high = low.saturating_add(SEGMENT_SIZE);
+        let needed = high.isqrt() + 1;
+
+        self.segment = if needed > MAX_BASE_PRIME {
+            (low..high).filter(|&n| is_prime_miller_rabin(n, 0)).collect()
+        } else {
+            // Make sure the base primes reach √high, doubling the bound to amortize
+            if self.base_primes.last().is_none_or(|&p| p < needed) {
+                self.base_primes = primes_up_to((needed * 2).min(MAX_BASE_PRIME));
+            }
+            sieve_segment(low, high, &self.base_primes)
+        };
+        self.position = 0;
+        self.low = high;
+        self.exhausted = high == u64::MAX;
+    }
+}
+
+impl Default for PrimeIterator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for PrimeIterator {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.position >= self.segment.len() {
+            if self.exhausted {
+                return None;
+            }
+            self.refill();
+        }
+
+        let prime = self.segment[self.position];
+        self.position += 1;
+        Some(prime)
+    }
+}
+
+/// Finds the smallest prime strictly greater than n
+///
+/// Steps through the odd numbers above n with Miller-Rabin, never past
+/// u64::MAX.
+///
+/// # Returns
+///
+/// The next prime, or `None` if n ≥ `LARGEST_U64_PRIME`, since no larger
+/// prime fits in a u64
+pub fn next_prime(n: u64) -> Option<u64> {
+    if n < 2 {
+        return Some(2);
+    }
+    // First odd number above n
+    let mut candidate = n.checked_add(1)? | 1;
+    loop {
+        if is_prime_miller_rabin(candidate, 0) {
+            return Some(candidate);
+        }
+        candidate = candidate.checked_add(2)?;
+    }
+}
+
+/// Collects primes from 2 upward until a predicate says to stop
+///
+/// Streams primes from a `PrimeIterator` and calls `stop` on each one; the
+/// first prime for which it returns `true` ends the stream and is not included.
+/// Useful when the bound is not known in advance, for instance "primes until
+/// their running sum exceeds X" (the closure is `FnMut`, so it may keep state).
+///
+/// # Arguments
+///
+/// * `stop` - Predicate called on each prime in turn
+///
+/// # Returns
+///
+/// All primes before the first one accepted by `stop`, in ascending order
+pub fn primes_until<F: FnMut(u64) -> bool>(mut stop: F) -> Vec<u64> {
+    PrimeIterator::new().take_while(|&p| !stop(p)).collect()
+}
+
+/// Streaming prime generator that only remembers the most recent primes
+///
+/// Wraps a `PrimeIterator` and keeps a sliding window of the last `capacity`
+/// primes in a `VecDeque`, so analyses such as prime gaps can run over an
+/// unbounded sequence in constant memory.
+pub struct WindowedPrimes {
+    window: VecDeque<u64>,
+    capacity: usize,
+    source: PrimeIterator,
+}
+
+impl WindowedPrimes {
+    /// Creates a generator remembering at most `capacity` primes
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Size of the sliding window, at least 1
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        WindowedPrimes {
+            window: VecDeque::with_capacity(capacity),
+            capacity,
+            source: PrimeIterator::new(),
+        }
+    }
+
+    /// Advances to the next prime and pushes it into the window
+    ///
+    /// The oldest prime is dropped once the window is full.
+    pub fn next_prime(&mut self) -> Option<u64> {
+        let prime = self.source.next()?;
+        if self.window.len() == self.capacity {
+            self.window.pop_front();
+        }
+        self.window.push_back(prime);
+        Some(prime)
+    }
+
+    /// Returns the `k` most recent primes in ascending order
+    ///
+    /// Yields fewer than `k` primes if the window does not hold that many yet.
+    pub fn recent(&self, k: usize) -> impl Iterator<Item = u64> + '_ {
+        let skip = self.window.len().saturating_sub(k);
+        self.window.iter().skip(skip).copied()
+    }
+
+    /// Returns the number of primes currently held in the window
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Returns `true` if no prime has been generated yet
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+}
+
+/// Extension trait adding `.primes()` to integer ranges
+///
+/// Lets users write `(2u64..=100).primes()` to iterate over the primes in a
+/// range. The range is sieved lazily, one segment at a time, so only the
+/// segments actually consumed are ever computed. The base primes grow with
+/// the segments too, so a range with a huge upper bound costs nothing until
+/// its far end is reached.
+pub trait RangePrimes {
+    /// Returns an iterator over the primes in the range, in ascending order
+    fn primes(self) -> PrimesInRange;
+}
+
+impl RangePrimes for RangeInclusive<u64> {
+    fn primes(self) -> PrimesInRange {
+        if self.is_empty() {
+            return PrimesInRange::empty();
+        }
+        let (low, high) = self.into_inner();
+        PrimesInRange::new(low, high)
+    }
+}
+
+impl RangePrimes for Range<u64> {
+    fn primes(self) -> PrimesInRange {
+        if self.is_empty() {
+            return PrimesInRange::empty();
+        }
+        PrimesInRange::new(self.start, self.end - 1)
+    }
+}
+
+/// Iterator over the primes of a bounded range, created by `RangePrimes::primes`
+///
+/// Segments are sieved on demand from both ends of the range, so the iterator
+/// can also be consumed from the top down with `next_back` or `rev`.
+pub struct PrimesInRange {
+    /// Primes of the lowest sieved segment not yet yielded
+    front: std::vec::IntoIter<u64>,
+    /// Primes of the highest sieved segment not yet yielded
+    back: std::vec::IntoIter<u64>,
+    /// Inclusive bounds of the part of the range not sieved yet, `None` once
+    /// the whole range is sieved
+    unsieved: Option<(u64, u64)>,
+    /// Inclusive upper bound of the range
+    high: u64,
+    /// All primes up to `base_limit`
+    base_primes: Vec<u64>,
+    /// Bound up to which `base_primes` has been sieved
+    base_limit: u64,
+}
+
+impl PrimesInRange {
+    fn new(low: u64, high: u64) -> Self {
+        PrimesInRange {
+            front: Vec::new().into_iter(),
+            back: Vec::new().into_iter(),
+            unsieved: Some((low, high)),
+            high,
+            base_primes: Vec::new(),
+            base_limit: 0,
+        }
+    }
+
+    fn empty() -> Self {
+        PrimesInRange {
+            front: Vec::new().into_iter(),
+            back: Vec::new().into_iter(),
+            unsieved: None,
+            high: 0,
+            base_primes: Vec::new(),
+            base_limit: 0,
+        }
+    }
+
+    /// Sieves the inclusive segment [low, high] and returns its primes
+    fn sieve(&mut self, low: u64, high: u64) -> std::vec::IntoIter<u64> {
+        // Make sure the base primes reach √high, doubling the bound to amortize
+        // but never sieving past √ of the range's upper bound
+        let needed = high.isqrt();
+        if self.base_limit < needed {
+            self.base_limit = needed.saturating_mul(2).min(self.high.isqrt());
+            self.base_primes = primes_up_to(self.base_limit);
+        }
+
+        // u64::MAX itself is composite, so the half-open bound may saturate
+        sieve_segment(low, high.saturating_add(1), &self.base_primes).into_iter()
+    }
+}
+
+impl Iterator for PrimesInRange {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            if let Some(prime) = self.front.next() {
+                return Some(prime);
+            }
+
+            // Once everything is sieved, the rest sits in the back segment
+            let Some((low, high)) = self.unsieved else {
+                return self.back.next();
+            };
+            let segment_high = low.saturating_add(SEGMENT_SIZE - 1).min(high);
+            self.unsieved = (segment_high < high).then(|| (segment_high + 1, high));
+            self.front = self.sieve(low, segment_high);
+        }
+    }
+}
+
+impl DoubleEndedIterator for PrimesInRange {
+    fn next_back(&mut self) -> Option<u64> {
+        loop {
+            if let Some(prime) = self.back.next_back() {
+                return Some(prime);
+            }
+
+            let Some((low, high)) = self.unsieved else {
+                return self.front.next_back();
+            };
+            let segment_low = high.saturating_sub(SEGMENT_SIZE - 1).max(low);
+            self.unsieved = (segment_low > low).then(|| (low, segment_low - 1));
+            self.back = self.sieve(segment_low, high);
+        }
+    }
+}
+
+/// Iterator over the primes up to a limit, in either direction
+///
+/// Iterates forward like `PrimeIterator`, but stops at `limit` and also
+/// implements `DoubleEndedIterator`, so `next_back` enumerates the largest
+/// primes below the limit first. Both ends are sieved lazily, one segment at
+/// a time.
+pub struct BoundedPrimeIterator {
+    inner: PrimesInRange,
+}
+
+impl BoundedPrimeIterator {
+    /// Creates an iterator over the primes p ≤ limit
+    pub fn new(limit: u64) -> Self {
+        BoundedPrimeIterator {
+            inner: (0..=limit).primes(),
+        }
+    }
+}
+
+impl Iterator for BoundedPrimeIterator {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.inner.next()
+    }
+}
+
+impl DoubleEndedIterator for BoundedPrimeIterator {
+    fn next_back(&mut self) -> Option<u64> {
+        self.inner.next_back()
+    }
+}
+
+/// Wheel of residues coprime to a product of small primes
+///
+/// A wheel built from the primes 2, 3 and 5 has modulus 30 and 8 spokes: the
+/// residues 1, 7, 11, 13, 17, 19, 23 and 29. Every number coprime to 30 is a spoke
+/// plus a multiple of 30, so trial division or sieving can skip the other
+/// 22 residues in each turn of the wheel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wheel {
+    modulus: u64,
+    spokes: Vec<u64>,
+}
+
+impl Wheel {
+    /// Builds the wheel for the given primes
+    ///
+    /// # Arguments
+    ///
+    /// * `primes` - The wheel primes; the modulus is their product
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the primes is 0 or 1, which would leave the wheel with
+    /// no spokes, or if their product overflows a u64. Wheels are only useful
+    /// for a handful of primes anyway, as the spoke count grows quickly.
+    pub fn new(primes: &[u64]) -> Self {
+        if let Some(p) = primes.iter().find(|&&p| p < 2) {
+            panic!("wheel primes must be at least 2, got {}", p);
+        }
+        let modulus = primes
+            .iter()
+            .try_fold(1u64, |product, &p| product.checked_mul(p))
+            .expect("product of the wheel primes overflows u64");
+
+        let spokes = (1..=modulus)
+            .filter(|&r| primes.iter().all(|&p| !r.is_multiple_of(p)))
+            .collect();
+
+        Wheel { modulus, spokes }
+    }
+
+    /// Returns the product of the wheel primes
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Returns the residues in [1, modulus] coprime to every wheel prime
+    pub fn spokes(&self) -> &[u64] {
+        &self.spokes
+    }
+
+    /// Returns an iterator over the numbers coprime to every wheel prime
+    ///
+    /// Candidates are produced in ascending order starting above 1; the wheel
+    /// primes themselves are not produced. The iterator ends at u64::MAX.
+    pub fn candidates(&self) -> WheelCandidates<'_> {
+        WheelCandidates {
+            wheel: self,
+            base: 0,
+            index: 0,
+        }
+    }
+}
+
+/// Iterator over the candidates of a `Wheel`, created by `Wheel::candidates`
+pub struct WheelCandidates<'a> {
+    wheel: &'a Wheel,
+    /// Multiple of the modulus at the start of the current turn
+    base: u64,
+    /// Next spoke to produce in the current turn
+    index: usize,
+}
+
+impl Iterator for WheelCandidates<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            if self.index == self.wheel.spokes.len() {
+                self.index = 0;
+                self.base = self.base.checked_add(self.wheel.modulus)?;
+            }
+
+            let candidate = self.base.checked_add(self.wheel.spokes[self.index])?;
+            self.index += 1;
+            if candidate > 1 {
+                return Some(candidate);
+            }
+        }
+    }
+}
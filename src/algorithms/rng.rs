@@ -0,0 +1,66 @@
+/// Source of random 64-bit values used by randomized algorithms
+///
+/// Implement this trait to plug your own generator into functions such as
+/// `is_prime_miller_rabin_extra`. Wrapping an existing source is also an easy
+/// way to instrument how many random values an algorithm consumes.
+pub trait RandomSource {
+    /// Returns the next random 64-bit value
+    fn next_u64(&mut self) -> u64;
+}
+
+/// SplitMix64 pseudo-random number generator
+///
+/// A tiny, fast and seedable generator. It is not cryptographically secure,
+/// but its output is fully reproducible for a given seed, which is what
+/// testing and benchmarking need.
+///
+/// # References
+///
+/// See [SplitMix64](https://prng.di.unimi.it/splitmix64.c)
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Creates a new generator from the given seed
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Initial state; the same seed always yields the same sequence
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Draws a uniformly distributed value in [0, bound), for bound > 0
+///
+/// Uses one random word when bound ≤ 2^64 and two otherwise, and rejects the
+/// draws falling in the last, incomplete copy of [0, bound) so that `%` does
+/// not favor small values.
+pub(crate) fn uniform_below<R: RandomSource>(rng: &mut R, bound: u128) -> u128 {
+    let wide = bound > 1u128 << 64;
+    let max_draw = if wide { u128::MAX } else { u64::MAX as u128 };
+    // Accepting draws up to `limit` leaves a multiple of bound values
+    let limit = max_draw - (max_draw % bound + 1) % bound;
+
+    loop {
+        let mut draw = rng.next_u64() as u128;
+        if wide {
+            draw = (draw << 64) | rng.next_u64() as u128;
+        }
+        if draw <= limit {
+            return draw % bound;
+        }
+    }
+}
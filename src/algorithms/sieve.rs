@@ -1,18 +1,149 @@
-use super::PrimalityTest;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::arith::{overflow_add, trial_division_limit};
+use super::{trivial_primality, AlgorithmInfo, Assessment, Certainty, Complexity, PrimalityTest};
 use num_traits::{PrimInt, ToPrimitive, FromPrimitive};
 
 /// Implementation of the Sieve of Eratosthenes primality test
 ///
 /// This algorithm tests primality using trial division by all odd numbers
 /// up to the square root of n. It's efficient for small to medium-sized numbers.
+/// Constructed with `SievePolicy::Bitset`, it instead sieves every number up to
+/// a limit once and answers queries below it with a table lookup.
 ///
 /// # Performance
 ///
-/// - Time complexity: O(√n)
-/// - Space complexity: O(1)
+/// - Time complexity: O(√n), or O(1) below the bitset limit
+/// - Space complexity: O(1), or O(limit) bits for the bitset
 /// - Best for: Numbers < 10 million
-#[derive(Default)]
-pub struct SieveAlgorithm;
+#[derive(Clone, Debug, Default)]
+pub struct SieveAlgorithm {
+    policy: SievePolicy,
+    /// The sieved table, present with `SievePolicy::Bitset`
+    table: Option<PrecomputedSieve>,
+}
+
+/// How `SieveAlgorithm` answers a query
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SievePolicy {
+    /// Trial division on demand: no memory, O(√n) per query
+    #[default]
+    TrialDivision,
+    /// Sieve once up to `limit` and look queries up in a bitset
+    ///
+    /// Uses limit / 16 bytes; queries above the limit fall back to trial division.
+    Bitset {
+        /// Inclusive upper bound of the sieved range
+        limit: u64,
+    },
+}
+
+impl SieveAlgorithm {
+    /// Creates a sieve answering queries according to `policy`
+    ///
+    /// With `SievePolicy::Bitset` the whole range is sieved here, once, so that
+    /// every later query below the limit is a single lookup.
+    pub fn with_policy(policy: SievePolicy) -> Self {
+        let table = match policy {
+            SievePolicy::TrialDivision => None,
+            SievePolicy::Bitset { limit } => Some(PrecomputedSieve::new(limit)),
+        };
+        SieveAlgorithm { policy, table }
+    }
+
+    /// Returns the policy this sieve was created with
+    pub fn policy(&self) -> SievePolicy {
+        self.policy
+    }
+
+    /// Looks n up in the bitset, or `None` if n lies above the sieved range
+    fn lookup<N: PrimInt + ToPrimitive + FromPrimitive>(&self, n: N) -> Option<bool> {
+        self.table.as_ref()?.is_prime(n.to_u64()?)
+    }
+}
+
+/// Primality table of every number up to a limit, sieved once
+///
+/// Stores one bit per odd number, bit i standing for 2i + 1, so the table
+/// takes limit / 16 bytes. Lookups are O(1), and counting primes works a
+/// word at a time with `count_ones`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrecomputedSieve {
+    limit: u64,
+    /// Bit i is set iff 2i + 1 is prime
+    bits: Vec<u64>,
+}
+
+impl PrecomputedSieve {
+    /// Sieves every number up to and including `limit`
+    pub fn new(limit: u64) -> Self {
+        let flags = odd_composite_flags(limit);
+        let mut bits = vec![0u64; flags.len().div_ceil(64)];
+        for (i, _) in flags.iter().enumerate().skip(1).filter(|&(_, &composite)| !composite) {
+            bits[i / 64] |= 1 << (i % 64);
+        }
+        PrecomputedSieve { limit, bits }
+    }
+
+    /// Returns the inclusive upper bound of the table
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Looks n up in the table
+    ///
+    /// # Returns
+    ///
+    /// `Some(true)` if n is prime, `Some(false)` if it is not, or `None` if n
+    /// lies above the limit
+    pub fn is_prime(&self, n: u64) -> Option<bool> {
+        if n > self.limit {
+            return None;
+        }
+        if n < 3 || n.is_multiple_of(2) {
+            return Some(n == 2);
+        }
+        let i = (n / 2) as usize;
+        Some(self.bits[i / 64] >> (i % 64) & 1 == 1)
+    }
+
+    /// Counts the primes up to and including the limit
+    pub fn count(&self) -> u64 {
+        self.count_odd_bits(self.bits.len() * 64) + (self.limit >= 2) as u64
+    }
+
+    /// Counts the primes strictly below n
+    ///
+    /// # Panics
+    ///
+    /// Panics if n exceeds limit + 1, since the table says nothing about the
+    /// numbers above the limit
+    pub fn count_below(&self, n: u64) -> u64 {
+        assert!(
+            n <= self.limit.saturating_add(1),
+            "count_below({}) exceeds the sieved limit {}",
+            n,
+            self.limit
+        );
+        // The odd numbers below n are 2i + 1 for i < n / 2
+        self.count_odd_bits((n / 2) as usize) + (n > 2) as u64
+    }
+
+    /// Counts the set bits among the first `len` bits, i.e. the odd primes
+    /// 2i + 1 with i < len
+    fn count_odd_bits(&self, len: usize) -> u64 {
+        let full = (len / 64).min(self.bits.len());
+        let mut count: u64 = self.bits[..full].iter().map(|word| word.count_ones() as u64).sum();
+
+        let rest = len % 64;
+        if rest != 0 && full < self.bits.len() {
+            count += (self.bits[full] & ((1u64 << rest) - 1)).count_ones() as u64;
+        }
+        count
+    }
+}
 
 impl<N: PrimInt + ToPrimitive + FromPrimitive> PrimalityTest<N> for SieveAlgorithm {
     fn name(&self) -> &'static str {
@@ -20,7 +151,30 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive> PrimalityTest<N> for SieveAlgorit
     }
 
     fn is_prime(&self, n: N) -> bool {
-        is_prime_sieve(n)
+        self.lookup(n).unwrap_or_else(|| is_prime_sieve(n))
+    }
+
+    fn estimate_cost(&self, n: N) -> u64 {
+        if self.lookup(n).is_some() {
+            return 1;
+        }
+        // One division per odd candidate up to √n
+        (n.to_f64().unwrap_or(f64::MAX).sqrt() / 2.0) as u64 + 1
+    }
+
+    fn describe(&self) -> AlgorithmInfo {
+        AlgorithmInfo {
+            name: PrimalityTest::<N>::name(self),
+            complexity: Complexity::Sqrt,
+        }
+    }
+
+    fn assess(&self, n: N) -> Assessment {
+        // Trial division up to √n and the sieve both leave no room for error
+        Assessment {
+            verdict: PrimalityTest::<N>::is_prime(self, n),
+            certainty: Certainty::Proven,
+        }
     }
 }
 
@@ -40,33 +194,506 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive> PrimalityTest<N> for SieveAlgorit
 /// # Correctness
 ///
 /// This function is 100% deterministic and always returns the correct result.
+/// The divisor bound comes from the exact `integer_sqrt`, never from a float
+/// square root, so no factor near √n is skipped for lack of precision.
 pub fn is_prime_sieve<N: PrimInt + ToPrimitive + FromPrimitive>(n: N) -> bool {
     let zero = N::zero();
-    let one = N::one();
     let two = N::from_u64(2).unwrap();
-    
+
     // Handle edge cases
-    if n <= one {
+    if let Some(result) = trivial_primality(n) {
+        return result;
+    }
+
+    // Check odd divisors up to sqrt(n)
+    let limit = trial_division_limit(n);
+    let mut i = N::from_u64(3).unwrap();
+    while i <= limit {
+        if n % i == zero {
+            return false;
+        }
+        i = overflow_add(i, two, "is_prime_sieve");
+    }
+
+    true
+}
+
+/// Trial division like `is_prime_sieve`, reporting how many divisions it took
+///
+/// Makes the O(√n) cost of trial division concrete: a prime p needs about
+/// √p / 2 divisions, one per odd candidate, while a composite stops at its
+/// smallest odd factor. The trivial cases (n < 4 and even n) are settled
+/// without dividing and report 0.
+///
+/// # Returns
+///
+/// The verdict, `true` if n is prime, and the number of trial divisions
+/// performed to reach it
+pub fn is_prime_sieve_instrumented(n: u64) -> (bool, u64) {
+    if let Some(result) = trivial_primality(n) {
+        return (result, 0);
+    }
+
+    let limit = n.isqrt();
+    let mut divisions = 0;
+    let mut i = 3;
+    while i <= limit {
+        divisions += 1;
+        if n.is_multiple_of(i) {
+            return (false, divisions);
+        }
+        i += 2;
+    }
+
+    (true, divisions)
+}
+
+/// Finds the smallest prime factor of a composite number by trial division
+///
+/// Performs the same divisions as `is_prime_sieve`, but reports the divisor
+/// that proves n composite instead of discarding it. The first divisor found
+/// is necessarily prime, which makes this a building block for factorization.
+///
+/// # Arguments
+///
+/// * `n` - The number to inspect
+///
+/// # Returns
+///
+/// `Some(p)` with the smallest prime factor p of a composite n, or `None` if n
+/// is prime. Numbers below 2 have no prime factors and also yield `None`.
+pub fn smallest_factor_sieve(n: u64) -> Option<u64> {
+    if n < 4 {
+        return None;
+    }
+    if n.is_multiple_of(2) {
+        return Some(2);
+    }
+
+    let limit = n.isqrt();
+    let mut i = 3;
+    while i <= limit {
+        if n.is_multiple_of(i) {
+            return Some(i);
+        }
+        i += 2;
+    }
+
+    None
+}
+
+/// Allocation-free primality test for u32, intended for embedded targets
+///
+/// Uses 6k±1 wheel trial division with an integer square root, so it needs
+/// neither heap allocation nor floating point. Only `core` arithmetic is used,
+/// which makes this the smallest-footprint path in the crate.
+///
+/// # Arguments
+///
+/// * `n` - The number to test for primality
+///
+/// # Returns
+///
+/// `true` if n is prime, `false` if n is composite or less than 2
+pub fn is_prime_u32_noalloc(n: u32) -> bool {
+    if n < 2 {
         return false;
     }
-    if n == two {
+    if n < 4 {
         return true;
     }
-    if n % two == zero {
+    if n.is_multiple_of(2) || n.is_multiple_of(3) {
         return false;
     }
 
-    // Check odd divisors up to sqrt(n)
-    let n_f64 = n.to_f64().unwrap();
-    let limit = N::from_u64(n_f64.sqrt() as u64 + 1).unwrap();
-    
-    let mut i = N::from_u64(3).unwrap();
+    // Every prime above 3 is of the form 6k ± 1
+    let limit = n.isqrt();
+    let mut i = 5;
     while i <= limit {
-        if n % i == zero {
+        if n.is_multiple_of(i) || n.is_multiple_of(i + 2) {
             return false;
         }
-        i = i + two;
+        i += 6;
     }
 
     true
 }
+
+/// Trial divisions performed by `is_prime_cancellable` between checks of its flag
+const CANCEL_CHECK_INTERVAL: u64 = 1 << 12;
+
+/// Trial division that can be stopped from another thread
+///
+/// Runs the same 6k±1 trial division as `is_prime_u32_noalloc` on a u64, and
+/// polls `cancel` every few thousand divisions. Useful from interactive
+/// front ends, where testing a large prime by trial division may take far
+/// longer than the user is willing to wait.
+///
+/// # Arguments
+///
+/// * `n` - The number to test for primality
+/// * `cancel` - Flag that another thread sets to abandon the computation
+///
+/// # Returns
+///
+/// `Some(verdict)` if the test finished, `None` if it was cancelled first
+pub fn is_prime_cancellable(n: u64, cancel: &AtomicBool) -> Option<bool> {
+    if n < 4 {
+        return Some(n >= 2);
+    }
+    if n.is_multiple_of(2) || n.is_multiple_of(3) {
+        return Some(false);
+    }
+
+    let limit = n.isqrt();
+    let mut i = 5;
+    let mut steps = 0u64;
+    while i <= limit {
+        if n.is_multiple_of(i) || n.is_multiple_of(i + 2) {
+            return Some(false);
+        }
+        i += 6;
+
+        steps += 1;
+        if steps.is_multiple_of(CANCEL_CHECK_INTERVAL) && cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+    }
+
+    Some(true)
+}
+
+/// Primality test usable in constant expressions
+///
+/// The same 6k±1 trial division as `is_prime_u32_noalloc`, written as a
+/// `const fn` over u64 so that primality can be checked at compile time, e.g.
+/// `const _: () = assert!(is_prime_const(97));` or in an array length. It uses
+/// only integer arithmetic and loops, with the integer square root computed
+/// by `u64::isqrt`.
+///
+/// # Limits
+///
+/// Compile-time evaluation performs about √n / 3 loop iterations, and rustc
+/// warns through the `long_running_const_eval` lint once a constant takes
+/// too long. Primes up to around 10^12 evaluate in well under a second;
+/// much larger primes are better left to run time.
+///
+/// # Returns
+///
+/// `true` if n is prime, `false` if n is composite or less than 2
+pub const fn is_prime_const(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n < 4 {
+        return true;
+    }
+    if n.is_multiple_of(2) || n.is_multiple_of(3) {
+        return false;
+    }
+
+    let limit = n.isqrt();
+    let mut i = 5;
+    while i <= limit {
+        if n.is_multiple_of(i) || n.is_multiple_of(i + 2) {
+            return false;
+        }
+        i += 6;
+    }
+
+    true
+}
+
+/// Generates all primes up to and including `limit`
+///
+/// This is the actual Sieve of Eratosthenes: it crosses out the multiples of
+/// every prime up to √limit and collects the numbers that remain.
+///
+/// # Arguments
+///
+/// * `limit` - Inclusive upper bound of the generated primes
+///
+/// # Returns
+///
+/// All primes p with p ≤ limit, in ascending order
+///
+/// # Performance
+///
+/// - Time complexity: O(limit log log limit)
+/// - Space complexity: O(limit)
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    if limit < 2 {
+        return Vec::new();
+    }
+
+    let flags = odd_composite_flags(limit);
+    let mut primes = vec![2];
+    primes.extend(
+        flags
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|&(_, &composite)| !composite)
+            .map(|(i, _)| 2 * i as u64 + 1),
+    );
+
+    primes
+}
+
+/// Number of integers sieved at once by the segmented sieves
+pub(crate) const SEGMENT_SIZE: u64 = 1 << 16;
+
+/// Errors reported by `SegmentedSieve`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SieveError {
+    /// The supplied base primes do not reach √high of the requested range
+    InsufficientBasePrimes {
+        /// The largest supplied base prime (0 if none were supplied)
+        largest: u64,
+        /// ⌊√high⌋, the bound up to which every prime must be supplied
+        required: u64,
+    },
+}
+
+impl fmt::Display for SieveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SieveError::InsufficientBasePrimes { largest, required } => write!(
+                f,
+                "base primes only reach {}, but the range needs primes up to {}",
+                largest, required
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SieveError {}
+
+/// Segmented Sieve of Eratosthenes for primes in arbitrary ranges
+///
+/// Sieves a range [low, high] one fixed-size segment at a time, using the
+/// primes up to √high as base primes. Memory use is O(√high + segment size)
+/// regardless of how wide the range is.
+///
+/// By default the base primes are computed for every query. Users who already
+/// have a list of small primes (e.g. loaded from a file) can supply it with
+/// `with_base_primes` to skip that recomputation when sieving huge ranges.
+#[derive(Clone, Debug, Default)]
+pub struct SegmentedSieve {
+    base_primes: Option<Vec<u64>>,
+}
+
+impl SegmentedSieve {
+    /// Creates a sieve which computes its base primes on demand
+    pub fn new() -> Self {
+        SegmentedSieve { base_primes: None }
+    }
+
+    /// Creates a sieve using a precomputed list of base primes
+    ///
+    /// # Arguments
+    ///
+    /// * `primes` - All primes up to some bound, in ascending order. The bound
+    ///   is validated against each query at sieve time.
+    pub fn with_base_primes(primes: Vec<u64>) -> Self {
+        SegmentedSieve { base_primes: Some(primes) }
+    }
+
+    /// Returns all primes in the inclusive range, in ascending order
+    ///
+    /// # Errors
+    ///
+    /// Returns `SieveError::InsufficientBasePrimes` if the sieve was built with
+    /// `with_base_primes` and some prime up to ⌊√high⌋ is larger than every
+    /// supplied prime. A list ending just short of ⌊√high⌋ is accepted when no
+    /// prime lies in between, so `primes_up_to(1000)` covers ranges up to 10^6.
+    pub fn primes_in_range(&self, range: RangeInclusive<u64>) -> Result<Vec<u64>, SieveError> {
+        let (low, high) = range.into_inner();
+        if low > high {
+            return Ok(Vec::new());
+        }
+
+        let required = high.isqrt();
+        let computed;
+        let base_primes = match &self.base_primes {
+            Some(primes) => {
+                let largest = primes.last().copied().unwrap_or(0);
+                // The list is enough as long as the next prime after it
+                // squares past high; it is found within a prime gap of largest
+                if (largest + 1..=required).any(is_prime_sieve) {
+                    return Err(SieveError::InsufficientBasePrimes { largest, required });
+                }
+                primes
+            }
+            None => {
+                computed = primes_up_to(required);
+                &computed
+            }
+        };
+
+        Ok(sieve_range(low, high, base_primes))
+    }
+}
+
+/// Sieves [low, high] segment by segment with base primes covering √high
+fn sieve_range(low: u64, high: u64, base_primes: &[u64]) -> Vec<u64> {
+    let mut primes = Vec::new();
+    let mut segment_low = low;
+    loop {
+        let segment_high = segment_low.saturating_add(SEGMENT_SIZE - 1).min(high);
+        // sieve_segment works on half-open ranges; u64::MAX itself is
+        // composite (divisible by 3), so it never needs to be included
+        primes.extend(sieve_segment(segment_low, segment_high.saturating_add(1), base_primes));
+
+        if segment_high == high {
+            break;
+        }
+        segment_low = segment_high + 1;
+    }
+    primes
+}
+
+/// Returns all primes in the inclusive range, in ascending order
+///
+/// Convenience wrapper around `SegmentedSieve::new().primes_in_range(range)`.
+pub fn primes_in_range(range: RangeInclusive<u64>) -> Vec<u64> {
+    SegmentedSieve::new()
+        .primes_in_range(range)
+        .expect("computed base primes always cover the range")
+}
+
+/// Returns all primes in the union of several inclusive ranges, in ascending order
+///
+/// The ranges may come in any order and may overlap: they are sorted and
+/// merged first, so every number is sieved at most once and every prime is
+/// listed once. The base primes are computed a single time, for the highest
+/// bound of all the ranges.
+///
+/// # Arguments
+///
+/// * `ranges` - The windows to sieve; empty ranges are ignored
+pub fn primes_in_ranges(ranges: &[RangeInclusive<u64>]) -> Vec<u64> {
+    let mut bounds: Vec<(u64, u64)> = ranges
+        .iter()
+        .filter(|range| !range.is_empty())
+        .map(|range| (*range.start(), *range.end()))
+        .collect();
+    bounds.sort_unstable();
+
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(bounds.len());
+    for (low, high) in bounds {
+        match merged.last_mut() {
+            // Overlapping or adjacent windows are sieved as one
+            Some((_, last_high)) if low <= last_high.saturating_add(1) => *last_high = (*last_high).max(high),
+            _ => merged.push((low, high)),
+        }
+    }
+
+    let Some(highest) = merged.iter().map(|&(_, high)| high).max() else {
+        return Vec::new();
+    };
+    let base_primes = primes_up_to(highest.isqrt());
+    merged
+        .into_iter()
+        .flat_map(|(low, high)| sieve_range(low, high, &base_primes))
+        .collect()
+}
+
+/// Generates all primes up to and including `limit` using multiple threads
+///
+/// The base primes up to √limit are computed serially, then the range is cut
+/// into independent segments which are sieved in parallel with rayon. Segment
+/// results are collected in order, so the output is ascending exactly like
+/// `primes_up_to`.
+///
+/// # Arguments
+///
+/// * `limit` - Inclusive upper bound of the generated primes
+///
+/// # Returns
+///
+/// All primes p with p ≤ limit, in ascending order
+#[cfg(feature = "parallel")]
+pub fn primes_up_to_parallel(limit: u64) -> Vec<u64> {
+    use rayon::prelude::*;
+
+    const PARALLEL_SEGMENT_SIZE: u64 = 1 << 18;
+
+    if limit < 2 {
+        return Vec::new();
+    }
+
+    let base_primes = primes_up_to(limit.isqrt());
+    let segments = (limit + 1).div_ceil(PARALLEL_SEGMENT_SIZE);
+
+    let chunks: Vec<Vec<u64>> = (0..segments)
+        .into_par_iter()
+        .map(|i| {
+            let low = i * PARALLEL_SEGMENT_SIZE;
+            let high = (low + PARALLEL_SEGMENT_SIZE).min(limit + 1);
+            sieve_segment(low, high, &base_primes)
+        })
+        .collect();
+
+    chunks.concat()
+}
+
+/// Sieves the half-open segment [low, high) and returns its primes
+///
+/// `base_primes` must contain every prime p with p² < high, in ascending order.
+pub(crate) fn sieve_segment(low: u64, high: u64, base_primes: &[u64]) -> Vec<u64> {
+    let len = (high - low) as usize;
+    let mut composite = vec![false; len];
+    for &p in base_primes {
+        let square = match p.checked_mul(p) {
+            Some(square) if square < high => square,
+            _ => break,
+        };
+        // The first multiple to cross out; near u64::MAX rounding low up to a
+        // multiple of p can overflow, in which case no multiple lies in range
+        let first = match low.div_ceil(p).checked_mul(p) {
+            Some(multiple) if multiple < high => multiple.max(square),
+            _ => continue,
+        };
+
+        // Walk by offset into the segment so the loop can never overflow u64
+        let mut index = (first - low) as usize;
+        while index < len {
+            composite[index] = true;
+            index += p as usize;
+        }
+    }
+
+    composite
+        .iter()
+        .enumerate()
+        .filter(|&(_, &c)| !c)
+        .map(|(i, _)| low + i as u64)
+        .filter(|&n| n >= 2)
+        .collect()
+}
+
+/// Sieves the odd numbers up to `limit`
+///
+/// Index `i` of the returned vector stands for the odd number 2i + 1 and is
+/// `true` when that number is composite. Index 0 (the number 1) is left unmarked,
+/// so callers have to skip it themselves.
+pub(crate) fn odd_composite_flags(limit: u64) -> Vec<bool> {
+    let size = (limit as usize).div_ceil(2);
+    let mut composite = vec![false; size];
+
+    let mut i = 1;
+    while (2 * i + 1) * (2 * i + 1) <= limit as usize {
+        if !composite[i] {
+            let p = 2 * i + 1;
+            let mut j = p * p / 2;
+            while j < size {
+                composite[j] = true;
+                j += p;
+            }
+        }
+        i += 1;
+    }
+
+    composite
+}
@@ -0,0 +1,416 @@
+use std::ops::RangeInclusive;
+
+use super::arith::{checked_affine, jacobi, ModArith};
+use super::miller_rabin::is_prime_miller_rabin;
+use super::sieve::is_prime_sieve;
+
+/// Proth's test for numbers of the form N = k × 2^n + 1
+///
+/// A Proth number has k odd and k < 2^n. Proth's theorem states that such an N
+/// is prime if and only if there exists a with a^((N-1)/2) ≡ -1 (mod N). Any
+/// quadratic non-residue modulo a prime N satisfies this, so the test searches
+/// for a base with Jacobi symbol (a / N) = -1 and performs a single modular
+/// exponentiation with it. Perfect squares admit no such base and are rejected
+/// up front.
+///
+/// # Arguments
+///
+/// * `k` - The odd multiplier, below 2^n
+/// * `n` - The exponent of two
+///
+/// # Returns
+///
+/// `true` if N is prime, `false` if N is composite. Inputs that do not describe
+/// a Proth number, or whose N does not fit into a u128, also return `false`.
+///
+/// # References
+///
+/// See [Proth's theorem](https://en.wikipedia.org/wiki/Proth%27s_theorem)
+pub fn is_proth_prime(k: u64, n: u32) -> bool {
+    if k.is_multiple_of(2) || n == 0 || n >= 128 || (n < 64 && k >= 1u64 << n) {
+        return false;
+    }
+
+    let Some(number) = (k as u128)
+        .checked_mul(1u128 << n)
+        .and_then(|shifted| shifted.checked_add(1))
+    else {
+        return false;
+    };
+
+    // The Jacobi symbol (a / N) is never -1 for a square N, so the base search
+    // would only end once a reached √N
+    let root = number.isqrt();
+    if root * root == number {
+        return false;
+    }
+
+    match u64::try_from(number) {
+        Ok(number) => proth_test(number),
+        Err(_) => proth_test(number),
+    }
+}
+
+/// Runs Proth's theorem on N, which must be a Proth number
+fn proth_test<N: ModArith>(number: N) -> bool {
+    let one = N::one();
+    let minus_one = number - one;
+    let half = minus_one >> 1;
+
+    let mut a = N::from_u64(2).unwrap();
+    while a < number {
+        match jacobi(a, number) {
+            -1 => return a.pow_mod(half, number) == minus_one,
+            0 => return false,
+            _ => a = a + one,
+        }
+    }
+
+    // Unreachable in practice: a prime N always has a non-residue below it,
+    // and a composite N is caught by its smallest factor giving a zero symbol
+    false
+}
+
+/// Largest exponent supported by `mersenne_prime_exponents`
+///
+/// The Lucas-Lehmer residues are computed in u128, and 2^127 - 1 is the
+/// largest Mersenne number below 2^128.
+pub const MAX_MERSENNE_EXPONENT: u32 = 127;
+
+/// Recognizes Mersenne numbers M_p = 2^p - 1
+///
+/// # Arguments
+///
+/// * `n` - The number to inspect
+///
+/// # Returns
+///
+/// `Some(p)` if n = 2^p - 1 (so 0 gives `Some(0)` and u64::MAX gives
+/// `Some(64)`), `None` otherwise
+pub fn is_mersenne_number(n: u64) -> Option<u32> {
+    // n + 1 is a power of two iff n's set bits are exactly its lowest bits
+    if n & n.wrapping_add(1) == 0 {
+        Some(n.count_ones())
+    } else {
+        None
+    }
+}
+
+/// Finds the exponents p ≤ max_p for which 2^p - 1 is prime
+///
+/// 2^p - 1 can only be prime when p is prime, so each prime p is checked with
+/// the Lucas-Lehmer test: with s_0 = 4 and s_(i+1) = s_i² - 2, M_p is prime iff
+/// s_(p-2) ≡ 0 (mod M_p).
+///
+/// # Arguments
+///
+/// * `max_p` - Inclusive upper bound of the exponents, at most `MAX_MERSENNE_EXPONENT`
+///
+/// # Returns
+///
+/// The exponents of the Mersenne primes up to 2^max_p - 1, in ascending order
+///
+/// # Panics
+///
+/// Panics if `max_p` exceeds `MAX_MERSENNE_EXPONENT`
+///
+/// # References
+///
+/// See [Lucas-Lehmer primality test](https://en.wikipedia.org/wiki/Lucas%E2%80%93Lehmer_primality_test)
+pub fn mersenne_prime_exponents(max_p: u32) -> Vec<u32> {
+    assert!(
+        max_p <= MAX_MERSENNE_EXPONENT,
+        "mersenne_prime_exponents supports exponents up to {}, got {}",
+        MAX_MERSENNE_EXPONENT,
+        max_p
+    );
+
+    (2..=max_p)
+        .filter(|&p| is_prime_sieve(p))
+        .filter(|&p| lucas_lehmer(p))
+        .collect()
+}
+
+/// Lucas-Lehmer test of M_p = 2^p - 1 for a prime p ≤ 127
+fn lucas_lehmer(p: u32) -> bool {
+    if p == 2 {
+        // The recurrence needs an odd p; M_2 = 3 is prime
+        return true;
+    }
+
+    let m = (1u128 << p) - 1;
+    let two = 2u128;
+    let mut s = 4u128;
+    for _ in 0..p - 2 {
+        s = s.mul_mod(s, m).sub_mod(two, m);
+    }
+    s == 0
+}
+
+/// Specialized test chosen by `is_prime_special_form` for b^e + c
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecialForm {
+    /// 2^p - 1, checked with the Lucas-Lehmer test
+    Mersenne,
+    /// 2^(2^k) + 1, checked with Pépin's test
+    Fermat,
+    /// k × 2^n + 1 with k odd and k < 2^n, checked with Proth's theorem
+    Proth,
+    /// Any other form, expanded and checked with Miller-Rabin
+    General,
+}
+
+/// Number of Fermat numbers F_k = 2^(2^k) + 1 small enough for Pépin's test
+/// in u128: F_0 to F_6 = 2^64 + 1
+const PEPIN_MAX_K: u32 = 6;
+
+/// Picks the specialized test `is_prime_special_form` runs on base^exp + offset
+///
+/// # Returns
+///
+/// The `SpecialForm` whose test applies, or `SpecialForm::General` when none
+/// does
+pub fn classify_special_form(base: u64, exp: u32, offset: i64) -> SpecialForm {
+    match (base, offset) {
+        (2, -1) => SpecialForm::Mersenne,
+        (2, 1) if exp.is_power_of_two() => SpecialForm::Fermat,
+        (_, 1) if proth_parameters(base, exp).is_some() => SpecialForm::Proth,
+        _ => SpecialForm::General,
+    }
+}
+
+/// Tests base^exp + offset for primality without expanding it when possible
+///
+/// Dispatches on the form of the number, as reported by
+/// `classify_special_form`:
+///
+/// - 2^p - 1 goes to the Lucas-Lehmer test, after rejecting composite p
+/// - 2^(2^k) + 1 goes to Pépin's test for k ≤ 6; F_7 to F_31 are all known
+///   to be composite, which covers every exponent that fits in a u32
+/// - b^e + 1 with b even, written k × 2^n + 1 with k odd and k < 2^n, goes
+///   to Proth's theorem
+/// - anything else is expanded and tested with Miller-Rabin
+///
+/// # Arguments
+///
+/// * `base` - The base b
+/// * `exp` - The exponent e
+/// * `offset` - The offset c, added to b^e
+///
+/// # Returns
+///
+/// `true` if base^exp + offset is prime, `false` otherwise (including when it
+/// is negative)
+///
+/// # Panics
+///
+/// Panics if the number needs a test on values above u128: a Mersenne
+/// number with a prime exponent above `MAX_MERSENNE_EXPONENT`, or a general
+/// form whose value does not fit in a u128
+///
+/// # References
+///
+/// See [Pépin's test](https://en.wikipedia.org/wiki/P%C3%A9pin%27s_test)
+pub fn is_prime_special_form(base: u64, exp: u32, offset: i64) -> bool {
+    match classify_special_form(base, exp, offset) {
+        SpecialForm::Mersenne => {
+            // 2^p - 1 can only be prime when p is prime
+            if !is_prime_sieve(exp) {
+                return false;
+            }
+            assert!(
+                exp <= MAX_MERSENNE_EXPONENT,
+                "Lucas-Lehmer supports exponents up to {}, got {}",
+                MAX_MERSENNE_EXPONENT,
+                exp
+            );
+            lucas_lehmer(exp)
+        }
+        SpecialForm::Fermat => {
+            let k = exp.trailing_zeros();
+            k <= PEPIN_MAX_K && pepin(k)
+        }
+        SpecialForm::Proth => {
+            let (k, n) = proth_parameters(base, exp).unwrap();
+            is_proth_prime(k, n)
+        }
+        SpecialForm::General => {
+            let power = (base as u128).checked_pow(exp);
+            match power.map(|power| power.checked_add_signed(offset as i128)) {
+                Some(Some(value)) => is_prime_miller_rabin(value, 0),
+                // Below zero, hence not prime
+                Some(None) if offset < 0 => false,
+                _ => panic!("{}^{} + {} does not fit in a u128", base, exp, offset),
+            }
+        }
+    }
+}
+
+/// Writes base^exp = k × 2^n with k odd, if that makes base^exp + 1 a Proth
+/// number that `is_proth_prime` accepts
+fn proth_parameters(base: u64, exp: u32) -> Option<(u64, u32)> {
+    if base == 0 || exp == 0 || !base.is_multiple_of(2) {
+        return None;
+    }
+    let n = base.trailing_zeros().checked_mul(exp).filter(|&n| n < 128)?;
+    let k = (base >> base.trailing_zeros()).checked_pow(exp)?;
+    // k × 2^n + 1 itself must fit in a u128, like any other special form
+    (k as u128).checked_mul(1 << n)?.checked_add(1)?;
+    (n >= 64 || k < 1 << n).then_some((k, n))
+}
+
+/// Pépin's test of F_k = 2^(2^k) + 1 for k ≤ 6: F_k is prime iff
+/// 3^((F_k - 1) / 2) ≡ -1 (mod F_k)
+fn pepin(k: u32) -> bool {
+    if k == 0 {
+        // F_0 = 3 is prime, but 3 is not a valid base modulo itself
+        return true;
+    }
+    let f = (1u128 << (1 << k)) + 1;
+    3u128.pow_mod((f - 1) / 2, f) == f - 1
+}
+
+/// Tests whether p is a Sophie Germain prime: p and 2p + 1 are both prime
+///
+/// # Returns
+///
+/// `true` if both are prime; `false` otherwise, including when 2p + 1
+/// overflows a u64
+pub fn is_sophie_germain_prime(p: u64) -> bool {
+    is_prime_miller_rabin(p, 0)
+        && checked_affine(p, 2, 1).is_some_and(|q| is_prime_miller_rabin(q, 0))
+}
+
+/// Tests whether q is a safe prime: q and (q - 1) / 2 are both prime
+///
+/// Safe primes are exactly the images 2p + 1 of the Sophie Germain primes p.
+///
+/// # Returns
+///
+/// `true` if q is a safe prime, `false` otherwise
+pub fn is_safe_prime(q: u64) -> bool {
+    if q < 5 || q.is_multiple_of(2) {
+        return false;
+    }
+    let p = (q - 1) / 2;
+    is_prime_miller_rabin(p, 0) && is_prime_miller_rabin(q, 0)
+}
+
+/// Number of candidate first terms examined by `cunningham_chain_first_kind`
+pub const CUNNINGHAM_SEARCH_WINDOW: u64 = 1 << 24;
+
+/// Finds a Cunningham chain of the first kind of at least `min_length` primes
+///
+/// A chain of the first kind is a sequence p, 2p + 1, 2(2p + 1) + 1, ... in
+/// which every term is prime. The search tries each prime p ≥ `start` in turn
+/// and follows its chain until a term is composite or would overflow a u64.
+/// It examines at most `CUNNINGHAM_SEARCH_WINDOW` candidates, and stops
+/// earlier once p is too large for a chain of the requested length to fit in
+/// a u64.
+///
+/// # Arguments
+///
+/// * `start` - Smallest first term to consider
+/// * `min_length` - Minimum number of primes in the chain
+///
+/// # Returns
+///
+/// The complete chain starting at the first suitable p, or `None` if no
+/// chain of that length starts within the search window
+pub fn cunningham_chain_first_kind(start: u64, min_length: usize) -> Option<Vec<u64>> {
+    // The last term of a chain of length L from p is 2^(L-1) × (p + 1) - 1
+    let max_start = match min_length {
+        0 => u64::MAX,
+        len => u32::try_from(len - 1)
+            .ok()
+            .and_then(|shift| u64::MAX.checked_shr(shift))
+            .unwrap_or(0),
+    };
+    let end = start
+        .saturating_add(CUNNINGHAM_SEARCH_WINDOW - 1)
+        .min(max_start);
+
+    (start..=end).filter(|&p| is_prime_miller_rabin(p, 0)).find_map(|p| {
+        let mut chain = vec![p];
+        while let Some(next) = checked_affine(*chain.last().unwrap(), 2, 1)
+            .filter(|&q| is_prime_miller_rabin(q, 0))
+        {
+            chain.push(next);
+        }
+        (chain.len() >= min_length).then_some(chain)
+    })
+}
+
+/// Largest distance from the target examined by `twin_prime_near`
+pub const TWIN_PRIME_SEARCH_RADIUS: u64 = 1 << 24;
+
+/// Finds the twin prime pair (p, p + 2) whose p is closest to `target`
+///
+/// Scans outward from `target`, testing candidates at distance 0, 1, 2, ...
+/// below and above it with Miller-Rabin, and returns the first pair found.
+/// Beyond (3, 5), every twin pair has p ≡ 5 (mod 6), so other candidates are
+/// skipped without a primality test.
+///
+/// # Arguments
+///
+/// * `target` - The value p should be close to
+///
+/// # Returns
+///
+/// The pair with p nearest to `target`, the lower one when two pairs are
+/// equally close, or `None` if no pair with p + 2 ≤ u64::MAX lies within
+/// `TWIN_PRIME_SEARCH_RADIUS` of it
+pub fn twin_prime_near(target: u64) -> Option<(u64, u64)> {
+    let is_twin = |p: u64| {
+        (p == 3 || p % 6 == 5)
+            && p <= u64::MAX - 2
+            && is_prime_miller_rabin(p, 0)
+            && is_prime_miller_rabin(p + 2, 0)
+    };
+
+    (0..=TWIN_PRIME_SEARCH_RADIUS).find_map(|d| {
+        let below = target.checked_sub(d).filter(|&p| is_twin(p));
+        let above = target.checked_add(d).filter(|&p| d > 0 && is_twin(p));
+        below.or(above).map(|p| (p, p + 2))
+    })
+}
+
+/// Searches for the quadratic n² + an + b producing the longest run of primes
+///
+/// For each coefficient pair, counts the consecutive values n = 0, 1, 2, ...
+/// for which n² + an + b is prime, as in Project Euler problem 27; Euler's
+/// n² + n + 41 famously gives 40. Negative values and values above u64::MAX
+/// end a run.
+///
+/// # Arguments
+///
+/// * `a_range` - Candidate linear coefficients
+/// * `b_range` - Candidate constant terms
+///
+/// # Returns
+///
+/// `(a, b, run)` for the pair with the longest run, the first one in
+/// ascending (a, b) order on ties, or `(0, 0, 0)` if no pair yields a prime
+pub fn best_quadratic_prime_run(a_range: RangeInclusive<i64>, b_range: RangeInclusive<i64>) -> (i64, i64, u64) {
+    let mut best = (0, 0, 0);
+    for a in a_range {
+        for b in b_range.clone() {
+            let run = quadratic_prime_run(a, b);
+            if run > best.2 {
+                best = (a, b, run);
+            }
+        }
+    }
+    best
+}
+
+/// Counts the consecutive n from 0 for which n² + an + b is prime
+fn quadratic_prime_run(a: i64, b: i64) -> u64 {
+    (0u64..)
+        .take_while(|&n| {
+            let n = n as i128;
+            let value = n * n + a as i128 * n + b as i128;
+            u64::try_from(value).is_ok_and(|value| is_prime_miller_rabin(value, 0))
+        })
+        .count() as u64
+}
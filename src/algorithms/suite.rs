@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use super::miller_rabin::is_prime_miller_rabin;
+use super::vectors::test_vectors;
+use super::PrimalityRegistry;
+
+/// Exponent of the accuracy in `AlgorithmScore::score`
+///
+/// High enough that a few wrong answers outweigh a large speed advantage:
+/// 99% accuracy keeps 90% of the score, 90% accuracy keeps 35%.
+const ACCURACY_WEIGHT: i32 = 10;
+
+/// Time taken by one algorithm over one size bucket
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BucketTiming {
+    /// Name of the algorithm, as returned by `PrimalityTest::name`
+    pub algorithm: &'static str,
+    /// Name of the bucket, as listed by `benchmark_buckets`
+    pub bucket: &'static str,
+    /// Number of inputs in the bucket
+    pub inputs: usize,
+    /// Number of inputs reported prime
+    pub primes: usize,
+    /// Wall-clock time for one pass over the bucket
+    pub elapsed: Duration,
+}
+
+impl BucketTiming {
+    /// Average time per input in nanoseconds
+    pub fn nanos_per_input(&self) -> f64 {
+        self.elapsed.as_nanos() as f64 / self.inputs.max(1) as f64
+    }
+}
+
+/// Outcome of `run_benchmark_suite`
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BenchmarkSuiteResult {
+    /// One timing per (algorithm, bucket) pair, grouped by algorithm in
+    /// registration order and then by bucket in `benchmark_buckets` order
+    pub timings: Vec<BucketTiming>,
+}
+
+impl BenchmarkSuiteResult {
+    /// Returns the timing of `algorithm` over `bucket`, if it was measured
+    pub fn get(&self, algorithm: &str, bucket: &str) -> Option<&BucketTiming> {
+        self.timings
+            .iter()
+            .find(|timing| timing.algorithm == algorithm && timing.bucket == bucket)
+    }
+}
+
+/// The size buckets of the criterion benchmarks, with their inputs
+///
+/// Each bucket is a list of `test_vectors`, named after the criterion
+/// benchmark group that measures it.
+pub fn benchmark_buckets() -> [(&'static str, &'static [u64]); 7] {
+    let vectors = test_vectors();
+    [
+        ("small_primes", vectors.small_primes),
+        ("small_composites", vectors.small_composites),
+        ("medium_primes", vectors.medium_primes),
+        ("medium_composites", vectors.medium_composites),
+        ("large_primes", vectors.large_primes),
+        ("very_large_primes", vectors.very_large_primes),
+        ("very_large_composites", vectors.very_large_composites),
+    ]
+}
+
+/// Times every registered algorithm over every benchmark bucket
+///
+/// A lightweight alternative to the criterion benchmarks for machine-readable
+/// records, e.g. in CI: each algorithm makes a single pass over each bucket
+/// of `benchmark_buckets`, without warm-up or statistics. With the `serde`
+/// feature the result implements `Serialize`, so it can be written as JSON.
+///
+/// # Arguments
+///
+/// * `registry` - The algorithms to time
+///
+/// # Returns
+///
+/// A `BenchmarkSuiteResult` with one timing per algorithm per bucket
+pub fn run_benchmark_suite(registry: &PrimalityRegistry<u64>) -> BenchmarkSuiteResult {
+    let buckets = benchmark_buckets();
+    let mut result = BenchmarkSuiteResult::default();
+
+    for algo in registry.algorithms() {
+        for &(bucket, numbers) in &buckets {
+            let started = Instant::now();
+            let primes = numbers.iter().filter(|&&n| algo.is_prime(std::hint::black_box(n))).count();
+            result.timings.push(BucketTiming {
+                algorithm: algo.name(),
+                bucket,
+                inputs: numbers.len(),
+                primes,
+                elapsed: started.elapsed(),
+            });
+        }
+    }
+
+    result
+}
+
+/// Speed and correctness of one algorithm on a workload, from `score_algorithms`
+///
+/// Ordered by `score`, so the best algorithm is the greatest.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AlgorithmScore {
+    /// Name of the algorithm, as returned by `PrimalityTest::name`
+    pub name: &'static str,
+    /// Wall-clock time for one pass over the workload
+    pub total_time: Duration,
+    /// Fraction of the workload on which the algorithm agrees with
+    /// deterministic Miller-Rabin, 1.0 for an empty workload
+    pub accuracy_vs_oracle: f64,
+    /// Combined metric in [0, 1]: 1 / (1 + log10(time / fastest time)),
+    /// times the accuracy raised to the power 10
+    pub score: f64,
+}
+
+impl PartialEq for AlgorithmScore {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for AlgorithmScore {}
+
+impl PartialOrd for AlgorithmScore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AlgorithmScore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.total_cmp(&other.score)
+    }
+}
+
+/// Ranks the registered algorithms on a workload by speed and correctness
+///
+/// Each algorithm makes one timed pass over `workload`, and its verdicts are
+/// checked against deterministic Miller-Rabin, which is exact for u64. The
+/// speed counts on a logarithmic scale, since timings of the algorithms span
+/// several orders of magnitude: the fastest algorithm gets 1, one ten times
+/// slower 1/2, one a hundred times slower 1/3. The score multiplies this by
+/// the accuracy to the power 10, so an algorithm that is fast but sometimes
+/// wrong ranks below a correct one that is somewhat slower.
+///
+/// # Arguments
+///
+/// * `registry` - The algorithms to rank
+/// * `workload` - The inputs to test, e.g. a sample of the caller's own data
+///
+/// # Returns
+///
+/// One `AlgorithmScore` per algorithm, best first
+pub fn score_algorithms(registry: &PrimalityRegistry<u64>, workload: &[u64]) -> Vec<AlgorithmScore> {
+    let expected: Vec<bool> = workload.iter().map(|&n| is_prime_miller_rabin(n, 0)).collect();
+
+    let mut scores: Vec<AlgorithmScore> = registry
+        .algorithms()
+        .iter()
+        .map(|algo| {
+            let started = Instant::now();
+            let verdicts: Vec<bool> = workload
+                .iter()
+                .map(|&n| algo.is_prime(std::hint::black_box(n)))
+                .collect();
+            let total_time = started.elapsed();
+
+            let agreeing = verdicts.iter().zip(&expected).filter(|(verdict, expected)| verdict == expected).count();
+            AlgorithmScore {
+                name: algo.name(),
+                total_time,
+                accuracy_vs_oracle: if workload.is_empty() { 1.0 } else { agreeing as f64 / workload.len() as f64 },
+                score: 0.0,
+            }
+        })
+        .collect();
+
+    // A zero duration is clamped to 1ns so that the ratio stays finite
+    let seconds = |time: Duration| time.max(Duration::from_nanos(1)).as_secs_f64();
+    let fastest = scores.iter().map(|entry| entry.total_time).min().unwrap_or_default();
+    for entry in &mut scores {
+        let speed = 1.0 / (1.0 + (seconds(entry.total_time) / seconds(fastest)).log10());
+        entry.score = speed * entry.accuracy_vs_oracle.powi(ACCURACY_WEIGHT);
+    }
+
+    scores.sort_by(|a, b| b.cmp(a));
+    scores
+}
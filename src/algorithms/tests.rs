@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod algorithm_tests {
-    use crate::{PrimalityRegistry, PrimalityTest};
+    use crate::{test_vectors, trivial_primality, PrimalityRegistry, PrimalityTest};
 
     /// Test suite that runs on all registered algorithms
     fn test_all_algorithms<F>(test_fn: F, test_name: &str)
@@ -54,7 +54,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_small_primes() {
-        let small_primes = vec![5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97];
+        let small_primes = test_vectors().small_primes;
         
         test_all_algorithms(
             |algo| {
@@ -66,7 +66,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_small_composites() {
-        let small_composites = vec![4, 6, 8, 9, 10, 12, 14, 15, 16, 18, 20, 21, 22, 24, 25, 26, 27, 28, 30, 32];
+        let small_composites = test_vectors().small_composites;
         
         test_all_algorithms(
             |algo| {
@@ -78,7 +78,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_medium_primes() {
-        let medium_primes = vec![101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193, 197];
+        let medium_primes = test_vectors().medium_primes;
         
         test_all_algorithms(
             |algo| {
@@ -90,7 +90,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_medium_composites() {
-        let medium_composites = vec![100, 102, 104, 105, 106, 108, 110, 111, 112, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124];
+        let medium_composites = test_vectors().medium_composites;
         
         test_all_algorithms(
             |algo| {
@@ -102,7 +102,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_large_primes() {
-        let large_primes = vec![1009, 10007, 100003, 1000003, 10000019, 100000007, 1000000007];
+        let large_primes = test_vectors().large_primes;
         
         test_all_algorithms(
             |algo| {
@@ -114,7 +114,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_large_composites() {
-        let large_composites = vec![1000, 10000, 100000, 1000000, 10000000, 100000000, 1000000000];
+        let large_composites = test_vectors().large_composites;
         
         test_all_algorithms(
             |algo| {
@@ -127,7 +127,7 @@ mod algorithm_tests {
     #[test]
     fn test_carmichael_numbers() {
         // Carmichael numbers are composite but pass Fermat's test
-        let carmichael = vec![561, 1105, 1729, 2465, 2821, 6601, 8911, 10585, 15841, 29341];
+        let carmichael = test_vectors().carmichael;
         
         test_all_algorithms(
             |algo| {
@@ -140,7 +140,7 @@ mod algorithm_tests {
     #[test]
     fn test_mersenne_primes() {
         // Mersenne primes: 2^p - 1 where p is prime
-        let mersenne_primes = vec![3, 7, 31, 127, 8191, 131071, 524287, 2147483647];
+        let mersenne_primes = test_vectors().mersenne_primes;
         
         test_all_algorithms(
             |algo| {
@@ -153,7 +153,7 @@ mod algorithm_tests {
     #[test]
     fn test_fermat_primes() {
         // Fermat primes: 2^(2^n) + 1
-        let fermat_primes = vec![3, 5, 17, 257, 65537];
+        let fermat_primes = test_vectors().fermat_primes;
         
         test_all_algorithms(
             |algo| {
@@ -318,11 +318,7 @@ mod algorithm_tests {
 
     #[test]
     fn test_very_large_primes() {
-        let very_large_primes = vec![
-            10_000_000_019,
-            100_000_000_003,
-            1_000_000_000_039,
-        ];
+        let very_large_primes = test_vectors().very_large_primes;
         
         test_all_algorithms(
             |algo| {
@@ -359,6 +355,36 @@ mod algorithm_tests {
         }
     }
 
+    #[test]
+    fn test_consistency_sweep() {
+        // Every input in 2..100000, with Miller-Rabin as the ground truth
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let oracle = registry.get_by_name("Miller-Rabin").unwrap();
+
+        for n in 2..100_000u64 {
+            let expected = oracle.is_prime(n);
+            for algo in registry.algorithms() {
+                assert_eq!(
+                    algo.is_prime(n), expected,
+                    "Algorithm '{}' disagrees with Miller-Rabin on number {}",
+                    algo.name(), n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_default_is_populated() {
+        assert_eq!(PrimalityRegistry::<u64>::default().len(), 3);
+        assert_eq!(PrimalityRegistry::<u64>::new().len(), 3);
+        assert!(PrimalityRegistry::<u64>::empty().is_empty());
+
+        let names = |registry: PrimalityRegistry<u64>| -> Vec<&'static str> {
+            registry.algorithms().iter().map(|algo| algo.name()).collect()
+        };
+        assert_eq!(names(PrimalityRegistry::default()), names(PrimalityRegistry::with_all_algorithms()));
+    }
+
     #[test]
     fn test_all_algorithms_registered() {
         let registry = PrimalityRegistry::<u64>::with_all_algorithms();
@@ -402,4 +428,2669 @@ mod algorithm_tests {
             "Algorithm names should be unique"
         );
     }
+
+    #[test]
+    fn test_trivial_primality_agrees_with_algorithms() {
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+
+        for n in 0..10u64 {
+            if let Some(expected) = trivial_primality(n) {
+                for algo in registry.algorithms() {
+                    assert_eq!(
+                        algo.is_prime(n), expected,
+                        "Algorithm '{}' disagrees with trivial_primality on {}",
+                        algo.name(), n
+                    );
+                }
+            }
+        }
+
+        // Odd numbers from 5 upward are left to the algorithms
+        assert_eq!(trivial_primality(5u64), None);
+        assert_eq!(trivial_primality(9u64), None);
+    }
+
+    #[test]
+    fn test_registry_debug_lists_names() {
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let debug = format!("{:?}", registry);
+
+        for algo in registry.algorithms() {
+            assert!(debug.contains(algo.name()), "{} missing from {}", algo.name(), debug);
+        }
+    }
+
+    #[test]
+    fn test_sort_by_complexity_class() {
+        use crate::Complexity;
+
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let mut infos: Vec<_> = registry.algorithms().iter().map(|a| a.describe()).collect();
+        infos.sort_by_key(|info| info.complexity);
+
+        let position = |name: &str| infos.iter().position(|info| info.name == name).unwrap();
+        assert!(position("Miller-Rabin") < position("Sieve of Eratosthenes"));
+        assert_eq!(infos[0].complexity, Complexity::PolyLog);
+        assert!(infos.iter().all(|info| info.complexity != Complexity::Unknown));
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let sieve = registry.get_by_name("Sieve of Eratosthenes").unwrap();
+        let miller_rabin = registry.get_by_name("Miller-Rabin").unwrap();
+
+        let large = 1_000_000_000_000_000_003u64;
+        assert!(miller_rabin.estimate_cost(large) * 100 < sieve.estimate_cost(large));
+
+        let inputs = [2u64, 100, 10_000, 1_000_000, 1 << 40, u64::MAX];
+        for algo in registry.algorithms() {
+            let costs: Vec<u64> = inputs.iter().map(|&n| algo.estimate_cost(n)).collect();
+            assert!(
+                costs.windows(2).all(|w| w[0] <= w[1]),
+                "estimates of '{}' are not monotonic: {:?}",
+                algo.name(), costs
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_accuracy_against_oracle() {
+        use crate::MillerRabinAlgorithm;
+
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let oracle = MillerRabinAlgorithm::default();
+        let report = registry.compare_accuracy(&oracle, 2..=10_000);
+
+        assert_eq!(report.len(), registry.algorithms().len());
+        assert!(report.contains(&("Sieve of Eratosthenes", 0)));
+        assert!(report.contains(&("Miller-Rabin", 0)));
+
+        // Bounds are inclusive: a single-element range still tests one input
+        let single = registry.compare_accuracy(&oracle, u64::MAX..=u64::MAX);
+        assert!(single.iter().all(|&(_, count)| count == 0));
+    }
+
+    #[test]
+    fn test_narrow_types_near_maximum() {
+        use crate::{is_prime_miller_rabin, is_prime_sieve, is_prime_zeta};
+
+        for n in 0..=u8::MAX {
+            let expected = is_prime_miller_rabin(n as u64, 0);
+            assert_eq!(is_prime_sieve(n), expected, "sieve u8 {}", n);
+            assert_eq!(is_prime_zeta(n), expected, "zeta u8 {}", n);
+        }
+        for n in u16::MAX - 1000..=u16::MAX {
+            let expected = is_prime_miller_rabin(n as u64, 0);
+            assert_eq!(is_prime_sieve(n), expected, "sieve u16 {}", n);
+            assert_eq!(is_prime_zeta(n), expected, "zeta u16 {}", n);
+        }
+        for n in u32::MAX - 300..=u32::MAX {
+            let expected = is_prime_miller_rabin(n as u64, 0);
+            assert_eq!(is_prime_sieve(n), expected, "sieve u32 {}", n);
+            assert_eq!(is_prime_zeta(n), expected, "zeta u32 {}", n);
+        }
+    }
+
+    #[test]
+    fn test_vectors_classification() {
+        use crate::is_prime_miller_rabin;
+
+        let vectors = test_vectors();
+        for n in vectors.primes() {
+            assert!(is_prime_miller_rabin(n, 0), "{} listed as prime", n);
+        }
+        for n in vectors.composites() {
+            assert!(!is_prime_miller_rabin(n, 0), "{} listed as composite", n);
+        }
+
+        let lists = [
+            vectors.small_primes, vectors.small_composites, vectors.medium_primes, vectors.medium_composites,
+            vectors.large_primes, vectors.large_composites, vectors.very_large_primes,
+            vectors.very_large_composites, vectors.carmichael, vectors.mersenne_primes, vectors.fermat_primes,
+        ];
+        for list in lists {
+            assert!(!list.is_empty());
+            assert!(list.windows(2).all(|w| w[0] < w[1]), "list not sorted: {:?}", list);
+        }
+    }
+
+    #[test]
+    fn test_assess_certainty() {
+        use crate::{Certainty, MillerRabinAlgorithm, SieveAlgorithm, ZetaAlgorithm};
+
+        for n in [2u64, 97, 561, 1_000_000_007] {
+            let sieve = PrimalityTest::<u64>::assess(&SieveAlgorithm::default(), n);
+            assert_eq!(sieve.verdict, SieveAlgorithm::default().is_prime(n));
+            assert_eq!(sieve.certainty, Certainty::Proven);
+            assert_eq!(PrimalityTest::<u64>::assess(&ZetaAlgorithm, n).certainty, Certainty::Proven);
+            assert_eq!(PrimalityTest::<u64>::assess(&MillerRabinAlgorithm::default(), n).certainty, Certainty::Proven);
+        }
+
+        // Beyond the proven bound of the deterministic witnesses a pass is only probable
+        let mersenne_89 = (1u128 << 89) - 1;
+        let assessment = MillerRabinAlgorithm::default().assess(mersenne_89);
+        assert!(assessment.verdict);
+        assert_eq!(assessment.certainty, Certainty::Probable);
+
+        // Custom witnesses prove compositeness but not primality
+        let weak = MillerRabinAlgorithm::with_witnesses(vec![2]);
+        assert_eq!(PrimalityTest::<u64>::assess(&weak, 2047).certainty, Certainty::Probable);
+        assert_eq!(PrimalityTest::<u64>::assess(&weak, 2049).certainty, Certainty::Proven);
+        assert_eq!(PrimalityTest::<u64>::assess(&weak, 97).certainty, Certainty::Probable);
+    }
+
+    #[test]
+    fn test_standalone_implementations_agree() {
+        use crate::{is_prime_miller_rabin, is_prime_sieve, primes_up_to, probable_prime_report};
+
+        let limit = 100_000u64;
+        let primes = primes_up_to(limit);
+        let mut next = primes.iter().peekable();
+        for n in 0..=limit {
+            let expected = next.next_if_eq(&&n).is_some();
+            assert_eq!(is_prime_miller_rabin(n, 0), expected, "miller-rabin on {}", n);
+            assert_eq!(is_prime_sieve(n), expected, "trial division on {}", n);
+            assert_eq!(probable_prime_report(n).verdict, expected, "baillie-psw on {}", n);
+        }
+    }
+
+    #[cfg(feature = "compare_primal")]
+    #[test]
+    fn test_agrees_with_primal() {
+        use crate::{is_prime_miller_rabin, is_prime_sieve, primes_up_to, RandomSource, SplitMix64};
+
+        let limit = 100_000usize;
+        let sieve = primal::Sieve::new(limit);
+        let expected: Vec<u64> = sieve.primes_from(0).map(|p| p as u64).collect();
+        assert_eq!(primes_up_to(limit as u64), expected);
+
+        for n in 0..=limit as u64 {
+            assert_eq!(is_prime_sieve(n), primal::is_prime(n), "trial division on {}", n);
+            assert_eq!(is_prime_miller_rabin(n, 0), primal::is_prime(n), "miller-rabin on {}", n);
+        }
+
+        // Random inputs across the whole u64 range
+        let mut rng = SplitMix64::new(940);
+        for _ in 0..10_000 {
+            let n = rng.next_u64();
+            assert_eq!(is_prime_miller_rabin(n, 0), primal::is_prime(n), "miller-rabin on {}", n);
+        }
+    }
+
+    #[test]
+    fn test_assertion_macros() {
+        use crate::{MillerRabinAlgorithm, SieveAlgorithm};
+
+        let sieve = SieveAlgorithm::default();
+        crate::assert_prime!(sieve, 97u64);
+        crate::assert_prime!(sieve, 65_521u16);
+        crate::assert_composite!(sieve, 561u64);
+        crate::assert_composite!(sieve, 1u32,);
+
+        let boxed: Box<dyn PrimalityTest<u64>> = Box::new(MillerRabinAlgorithm::default());
+        crate::assert_prime!(*boxed, 1_000_000_007);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: Sieve of Eratosthenes reported 91 as composite, expected prime")]
+    fn test_assert_prime_failure_message() {
+        crate::assert_prime!(crate::SieveAlgorithm::default(), 91u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: Sieve of Eratosthenes reported 89 as prime, expected composite")]
+    fn test_assert_composite_failure_message() {
+        crate::assert_composite!(crate::SieveAlgorithm::default(), 89u64);
+    }
+
+    #[test]
+    fn test_chain_short_circuits() {
+        use crate::{Assessment, Certainty, Chain, MillerRabinAlgorithm, SieveAlgorithm, TrialDivisionScreen};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        /// Miller-Rabin that counts how often the chain reaches it
+        struct Counted(Rc<Cell<usize>>);
+
+        impl PrimalityTest<u64> for Counted {
+            fn name(&self) -> &'static str {
+                "Counted Miller-Rabin"
+            }
+
+            fn is_prime(&self, n: u64) -> bool {
+                self.assess(n).verdict
+            }
+
+            fn assess(&self, n: u64) -> Assessment {
+                self.0.set(self.0.get() + 1);
+                MillerRabinAlgorithm::default().assess(n)
+            }
+        }
+
+        let calls = Rc::new(Cell::new(0));
+        let chain = Chain::new(vec![Box::new(TrialDivisionScreen::new(100))]).then(Counted(calls.clone()));
+
+        // Settled by the screen: small factors and n ≤ 100²
+        for (n, expected) in [(0, false), (2, true), (91, false), (9_973, true), (97 * 1_000_003, false)] {
+            assert_eq!(chain.is_prime(n), expected, "{}", n);
+        }
+        assert_eq!(calls.get(), 0);
+
+        // Only the survivors of the screen reach Miller-Rabin
+        let sieve = SieveAlgorithm::default();
+        for n in 10_001..10_101u64 {
+            assert_eq!(chain.is_prime(n), PrimalityTest::<u64>::is_prime(&sieve, n), "{}", n);
+        }
+        let survivors = (10_001..10_101u64).filter(|&n| (2..=100).all(|p| n % p != 0)).count();
+        assert_eq!(calls.get(), survivors);
+        assert_eq!(chain.assess(1_000_000_007).certainty, Certainty::Proven);
+
+        // Without a confirming algorithm the screen's guess is all there is
+        let screen_only = Chain::new(vec![Box::new(TrialDivisionScreen::new(100))]);
+        assert_eq!(
+            screen_only.assess(1_000_000_007),
+            Assessment { verdict: true, certainty: Certainty::HeuristicGuess }
+        );
+        assert_eq!(Chain::new(Vec::new()).assess(7).certainty, Certainty::HeuristicGuess);
+    }
+
+    #[test]
+    fn test_trial_division_screen_above_cached_table() {
+        use crate::{Assessment, Certainty, PrimalityTest, TrialDivisionScreen};
+
+        // Bounds above 2^16 use the screen's own table, sieved once in `new`
+        let screen = TrialDivisionScreen::new(200_000);
+        assert_eq!(
+            screen.assess(100_003 * 100_019),
+            Assessment { verdict: false, certainty: Certainty::Proven }
+        );
+        assert_eq!(
+            screen.assess(1_000_000_007),
+            Assessment { verdict: true, certainty: Certainty::Proven }
+        );
+        for n in 0..2_000u64 {
+            assert_eq!(screen.assess(n).verdict, crate::is_prime_sieve(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_errors_display_and_box() {
+        use crate::{PrimalityError, SegmentedSieve, SieveError, ZetaAlgorithm};
+        use std::error::Error;
+
+        let out_of_range = PrimalityError::OutOfRange { max_reliable: 1000 };
+        assert_eq!(
+            out_of_range.to_string(),
+            "input exceeds the largest value the algorithm handles reliably (1000)"
+        );
+        let insufficient = SieveError::InsufficientBasePrimes { largest: 499, required: 1095 };
+        assert_eq!(
+            insufficient.to_string(),
+            "base primes only reach 499, but the range needs primes up to 1095"
+        );
+
+        // Both compose with `?` in functions returning a boxed error
+        fn run(n: u64) -> Result<usize, Box<dyn Error>> {
+            let prime = ZetaAlgorithm.try_is_prime(n)?;
+            let primes = SegmentedSieve::with_base_primes(vec![2, 3, 5]).primes_in_range(0..=n)?;
+            Ok(primes.len() + prime as usize)
+        }
+        assert_eq!(run(20).unwrap(), 8);
+        let err = run(u64::MAX).unwrap_err();
+        assert!(err.downcast_ref::<PrimalityError>().is_some());
+        let err = run(1_000).unwrap_err();
+        assert_eq!(err.to_string(), "base primes only reach 5, but the range needs primes up to 31");
+    }
+
+    #[test]
+    fn test_fuzz_algorithms() {
+        use crate::{fuzz_algorithms, is_prime_miller_rabin, PrimalityTest, ZetaAlgorithm, ZETA_MAX_RELIABLE};
+
+        let report = fuzz_algorithms(200, 1_000_000, 42);
+        assert_eq!(report.iterations, 200);
+        let algorithms = PrimalityRegistry::<u64>::with_all_algorithms().algorithms().len();
+        assert_eq!(report.checks + report.skipped, 200 * algorithms);
+        assert_eq!(report.skipped, 0);
+        for divergence in &report.divergences {
+            assert_eq!(divergence.expected, is_prime_miller_rabin(divergence.n, 0));
+            assert_ne!(divergence.algorithm, "Miller-Rabin");
+        }
+
+        // The same seed replays the same run
+        assert_eq!(fuzz_algorithms(200, 1_000_000, 42), report);
+
+        // Zeta refuses inputs above its bound, which are skipped, not divergences
+        let report = fuzz_algorithms(3, ZETA_MAX_RELIABLE * 10, 7);
+        assert!(report.skipped > 0);
+        let zeta = PrimalityTest::<u64>::name(&ZetaAlgorithm);
+        assert!(report.divergences.iter().all(|d| d.algorithm != zeta || d.n <= ZETA_MAX_RELIABLE));
+    }
+
+    #[test]
+    fn test_results_map_keyed_by_algorithm_id() {
+        use crate::AlgorithmId;
+        use std::collections::HashMap;
+
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let results: HashMap<AlgorithmId, bool> = registry.results_map(97);
+        assert_eq!(results.len(), registry.algorithms().len());
+        assert!(results.values().all(|&verdict| verdict));
+        assert_eq!(results.get(&AlgorithmId("Miller-Rabin")), Some(&true));
+        assert!(!registry.results_map(91).values().any(|&verdict| verdict));
+
+        for algo in registry.algorithms() {
+            let id = AlgorithmId(algo.name());
+            assert_eq!(id.to_string(), algo.name());
+            assert!(results.contains_key(&id));
+        }
+    }
+
+    #[test]
+    fn test_algorithm_enum_round_trips_with_registry() {
+        use crate::{Algorithm, UnknownAlgorithmError};
+
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        for algo in Algorithm::all() {
+            let found = registry.get_by_name(&algo.to_string());
+            assert_eq!(found.map(|a| a.name()), Some(algo.name()), "{:?}", algo);
+            assert_eq!(Algorithm::try_from(algo.to_string().as_str()), Ok(algo));
+        }
+        assert_eq!(Algorithm::all().len(), registry.algorithms().len());
+
+        let err = Algorithm::try_from("AKS").unwrap_err();
+        assert_eq!(err, UnknownAlgorithmError { name: "AKS".to_string() });
+        assert_eq!(err.to_string(), "unknown algorithm name: \"AKS\"");
+    }
+
+    #[test]
+    fn test_fastest_correct_caches_per_bucket() {
+        use crate::AlgorithmId;
+
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+
+        // Trial division needs ~5 × 10^5 divisions here, Miller-Rabin a handful
+        // of exponentiations, so the warm-up settles on Miller-Rabin
+        let (id, verdict) = registry.fastest_correct(1_000_000_000_039).unwrap();
+        assert_eq!(id, AlgorithmId("Miller-Rabin"));
+        assert!(verdict);
+
+        // Same 13-digit bucket: the cached choice is reused, even for inputs
+        // that any algorithm would settle instantly
+        for n in [1_000_000_000_000u64, 1_000_000_000_061, 9_999_999_999_971] {
+            let (id, verdict) = registry.fastest_correct(n).unwrap();
+            assert_eq!(id, AlgorithmId("Miller-Rabin"));
+            assert_eq!(verdict, crate::is_prime_miller_rabin(n, 0), "{}", n);
+        }
+
+        // Small inputs get verdicts from whichever algorithm wins their bucket
+        for n in 0..100u64 {
+            assert_eq!(registry.fastest_correct(n).unwrap().1, crate::is_prime_sieve(n), "{}", n);
+        }
+        assert_eq!(PrimalityRegistry::<u64>::empty().fastest_correct(7), None);
+    }
+
+    #[test]
+    fn test_is_prime_str() {
+        use crate::{is_prime_str, parse_number, ParseError};
+
+        assert_eq!(is_prime_str(" 1_000_003 "), Ok(true));
+        assert_eq!(is_prime_str("1000000"), Ok(false));
+        assert_eq!(is_prime_str("\t18_446_744_073_709_551_557\n"), Ok(true));
+        // Values beyond u64 are tested too: 2^64 + 13 is prime
+        assert_eq!(is_prime_str("18446744073709551629"), Ok(true));
+        assert_eq!(parse_number("1__0_"), Ok(10));
+
+        assert_eq!(
+            is_prime_str("1,000,003"),
+            Err(ParseError::InvalidCharacter { character: ',', position: 1 })
+        );
+        assert_eq!(
+            is_prime_str("_7"),
+            Err(ParseError::InvalidCharacter { character: '_', position: 0 })
+        );
+        assert_eq!(
+            is_prime_str("-7"),
+            Err(ParseError::InvalidCharacter { character: '-', position: 0 })
+        );
+        assert_eq!(is_prime_str("   "), Err(ParseError::Empty));
+        assert_eq!(is_prime_str(&"9".repeat(40)), Err(ParseError::Overflow));
+        assert_eq!(
+            is_prime_str("1,000,003").unwrap_err().to_string(),
+            "invalid character ',' at position 1"
+        );
+    }
+
+    #[test]
+    fn test_is_prime_bytes_be() {
+        use crate::{is_prime_bytes_be, test_vectors, PrimalityError};
+
+        for p in test_vectors().primes() {
+            assert_eq!(is_prime_bytes_be(&p.to_be_bytes()), Ok(true), "{}", p);
+        }
+        for n in test_vectors().composites() {
+            assert_eq!(is_prime_bytes_be(&n.to_be_bytes()), Ok(false), "{}", n);
+        }
+
+        // Minimal encodings, padding and values beyond u64
+        assert_eq!(is_prime_bytes_be(&[0x03, 0xe8]), Ok(false));
+        assert_eq!(is_prime_bytes_be(&[0x0f, 0x42, 0x43]), Ok(true));
+        assert_eq!(is_prime_bytes_be(&[0, 0, 0, 0, 0, 0x0f, 0x42, 0x43]), Ok(true));
+        assert_eq!(is_prime_bytes_be(&((1u128 << 64) + 13).to_be_bytes()), Ok(true));
+        assert_eq!(is_prime_bytes_be(&u128::MAX.to_be_bytes()), Ok(false));
+        assert_eq!(is_prime_bytes_be(&[]), Ok(false));
+        assert_eq!(is_prime_bytes_be(&[2]), Ok(true));
+
+        let mut wide = vec![0u8; 4];
+        wide.extend_from_slice(&u128::MAX.to_be_bytes());
+        assert_eq!(is_prime_bytes_be(&wide), Ok(false));
+        wide[3] = 1;
+        let too_wide = PrimalityError::TooWide { max_bits: 128 };
+        assert_eq!(is_prime_bytes_be(&wide), Err(too_wide.clone()));
+        assert_eq!(too_wide.to_string(), "input does not fit in 128 bits");
+    }
+
+    #[test]
+    fn test_registry_from_iterator() {
+        let registry: PrimalityRegistry<u64> = PrimalityRegistry::<u64>::with_all_algorithms()
+            .into_iter()
+            .filter(|algo| algo.name() != "Riemann Zeta")
+            .collect();
+
+        let names: Vec<&str> = registry.algorithms().iter().map(|algo| algo.name()).collect();
+        assert_eq!(names, ["Sieve of Eratosthenes", "Miller-Rabin"]);
+        assert!(registry.get_by_name("Riemann Zeta").is_none());
+        assert!(registry.results_map(97).values().all(|&verdict| verdict));
+
+        let empty: PrimalityRegistry<u64> = std::iter::empty().collect();
+        assert!(empty.algorithms().is_empty());
+    }
+
+    /// Randomized stand-in that calls an odd input prime with probability 1/2
+    struct CoinFlip;
+
+    impl PrimalityTest<u64> for CoinFlip {
+        fn name(&self) -> &'static str {
+            "Coin flip"
+        }
+
+        fn is_prime(&self, n: u64) -> bool {
+            self.is_prime_seeded(n, &mut crate::SplitMix64::new(n))
+        }
+
+        fn is_prime_seeded(&self, n: u64, rng: &mut dyn crate::RandomSource) -> bool {
+            crate::trivial_primality(n).unwrap_or_else(|| rng.next_u64().is_multiple_of(2))
+        }
+    }
+
+    #[test]
+    fn test_compare_with_config_is_reproducible() {
+        use crate::{Algorithm, ComparisonConfig};
+
+        let mut registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        registry.register(CoinFlip);
+        let config = ComparisonConfig { seed: 980, ..ComparisonConfig::default() };
+
+        let first = registry.compare_with_config(&config, 0..=5_000);
+        let second = registry.compare_with_config(&config, 0..=5_000);
+        assert_eq!(format!("{:?}", first), format!("{:?}", second));
+        assert_eq!(first, second);
+
+        assert_eq!(first.rows.len(), 4);
+        for row in &first.rows[..3] {
+            assert_eq!((row.checked, row.disagreements, row.timed_out), (5_001, 0, false), "{}", row.algorithm);
+        }
+        let coin = &first.rows[3];
+        assert!(coin.disagreements > 0 && coin.checked == 5_001);
+
+        // Another seed changes the random choices, and so the report
+        let reseeded = ComparisonConfig { seed: 981, oracle: Algorithm::Sieve, ..config };
+        assert_ne!(registry.compare_with_config(&reseeded, 0..=5_000).rows[3], coin.clone());
+    }
+
+    #[test]
+    fn test_fn_algorithm_is_compared() {
+        use crate::{AlgorithmId, ComparisonConfig, FnAlgorithm};
+
+        let mut registry = PrimalityRegistry::<u64>::empty();
+        registry.register(FnAlgorithm::new("Always prime", |_| true));
+        registry.register(FnAlgorithm::new("Miller-Rabin fn", |n| crate::is_prime_miller_rabin(n, 0)));
+
+        let report = registry.compare_with_config(&ComparisonConfig::default(), 0..=100);
+        assert_eq!(report.rows.len(), 2);
+
+        let always = &report.rows[0];
+        assert_eq!(always.algorithm, AlgorithmId("Always prime"));
+        // Every one of the 101 inputs except the 25 primes is a disagreement
+        assert_eq!((always.checked, always.disagreements), (101, 76));
+        assert_eq!(report.rows[1].disagreements, 0);
+    }
+
+    #[test]
+    fn test_quick_composite_check() {
+        use crate::{is_prime_miller_rabin, quick_composite_check};
+
+        let screened = [2u64, 3, 5, 7, 11, 13];
+        for n in (0..100_000u64).chain(u64::MAX - 100_000..=u64::MAX) {
+            let has_small_factor = screened.iter().any(|&p| n.is_multiple_of(p));
+            let expected = match n {
+                0 | 1 => Some(false),
+                _ if screened.contains(&n) => Some(true),
+                _ if has_small_factor => Some(false),
+                _ => None,
+            };
+            assert_eq!(quick_composite_check(n), expected, "n = {}", n);
+            if let Some(verdict) = expected {
+                assert_eq!(verdict, is_prime_miller_rabin(n, 0), "n = {}", n);
+            }
+        }
+
+        // The first deferred composite is 17²
+        assert_eq!(quick_composite_check(17), None);
+        assert_eq!(quick_composite_check(289), None);
+        assert_eq!(quick_composite_check(13 * 1_000_003), Some(false));
+    }
+}
+
+#[cfg(test)]
+mod sieve_tests {
+    use crate::{is_prime_const, is_prime_sieve, is_prime_u32_noalloc, primes_up_to, smallest_factor_sieve};
+
+    // Evaluated by the compiler: a wrong verdict fails the build
+    const _: () = assert!(is_prime_const(97));
+    const _: () = assert!(is_prime_const(1_000_000_007));
+    const _: () = assert!(is_prime_const(1_000_000_000_039));
+    const _: () = assert!(!is_prime_const(1));
+    const _: () = assert!(!is_prime_const(91));
+    const _: () = assert!(!is_prime_const(561));
+    const _: () = assert!(!is_prime_const(1_000_000_007 * 3));
+
+    /// Usable wherever a constant is, e.g. in an array length
+    const PRIME_SLOTS: [u8; if is_prime_const(31) { 31 } else { 0 }] = [0; 31];
+
+    #[test]
+    fn test_is_prime_u32_noalloc() {
+        let primes = primes_up_to(100_000);
+        let mut expected = primes.iter().peekable();
+
+        for n in 0..100_000u32 {
+            let is_prime = expected.peek() == Some(&&(n as u64));
+            if is_prime {
+                expected.next();
+            }
+            assert_eq!(is_prime_u32_noalloc(n), is_prime, "wrong verdict for {}", n);
+        }
+
+        assert!(is_prime_u32_noalloc(4_294_967_291)); // largest u32 prime
+        assert!(!is_prime_u32_noalloc(u32::MAX));
+    }
+
+    #[test]
+    fn test_smallest_factor_sieve() {
+        assert_eq!(smallest_factor_sieve(15), Some(3));
+        assert_eq!(smallest_factor_sieve(13), None);
+        assert_eq!(smallest_factor_sieve(49), Some(7));
+        assert_eq!(smallest_factor_sieve(1_000_000), Some(2));
+        assert_eq!(smallest_factor_sieve(1_000_003 * 1_000_033), Some(1_000_003));
+
+        for n in 2..2000u64 {
+            assert_eq!(smallest_factor_sieve(n).is_none(), is_prime_sieve(n), "disagreement on {}", n);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_primes_up_to_parallel_matches_serial() {
+        use crate::primes_up_to_parallel;
+
+        for &limit in &[0u64, 1, 2, 100, 262_143, 262_144, 1_000_003, 10_000_000] {
+            assert_eq!(primes_up_to_parallel(limit), primes_up_to(limit), "mismatch for limit {}", limit);
+        }
+    }
+
+    #[test]
+    fn test_segmented_sieve_ranges() {
+        use crate::primes_in_range;
+
+        assert_eq!(primes_in_range(10..=30), vec![11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_in_range(0..=10), vec![2, 3, 5, 7]);
+        assert!(primes_in_range(24..=28).is_empty());
+
+        let expected: Vec<u64> = primes_up_to(300_000).into_iter().filter(|&p| p >= 100_000).collect();
+        assert_eq!(primes_in_range(100_000..=300_000), expected);
+
+        // Sieving right below u64::MAX must not overflow (base primes kept small for speed)
+        let base = primes_up_to(1000);
+        let low = u64::MAX - 100;
+        let survivors = crate::algorithms::sieve::sieve_segment(low, u64::MAX, &base);
+        let expected: Vec<u64> = (low..u64::MAX).filter(|n| base.iter().all(|p| n % p != 0)).collect();
+        assert_eq!(survivors, expected);
+        assert!(survivors.contains(&18_446_744_073_709_551_557));
+    }
+
+    #[test]
+    fn test_segmented_sieve_with_base_primes() {
+        use crate::{SegmentedSieve, SieveError};
+
+        let range = 1_000_000..=1_200_000;
+        let supplied = SegmentedSieve::with_base_primes(primes_up_to(1100));
+        let computed = SegmentedSieve::new();
+        assert_eq!(supplied.primes_in_range(range.clone()), computed.primes_in_range(range.clone()));
+
+        let insufficient = SegmentedSieve::with_base_primes(primes_up_to(500));
+        assert_eq!(
+            insufficient.primes_in_range(range),
+            Err(SieveError::InsufficientBasePrimes { largest: 499, required: 1095 })
+        );
+
+        // The largest prime up to √high is all that's needed, even when it
+        // falls short of √high itself (997 < 1000)
+        let range = 900_000..=1_000_000;
+        let exact = SegmentedSieve::with_base_primes(primes_up_to(1_000_000u64.isqrt()));
+        assert_eq!(exact.primes_in_range(range.clone()), computed.primes_in_range(range));
+    }
+
+    #[test]
+    fn test_sieve_policies_agree() {
+        use crate::{PrimalityTest, SieveAlgorithm, SievePolicy};
+
+        let limit = 200_000u64;
+        let trial = SieveAlgorithm::with_policy(SievePolicy::TrialDivision);
+        let bitset = SieveAlgorithm::with_policy(SievePolicy::Bitset { limit });
+        assert_eq!(bitset.policy(), SievePolicy::Bitset { limit });
+        assert_eq!(SieveAlgorithm::default().policy(), SievePolicy::TrialDivision);
+
+        // Identical answers below the limit, and above it through the fallback
+        for n in (0..=limit + 1_000).chain([1_000_000_007, u32::MAX as u64]) {
+            assert_eq!(
+                PrimalityTest::<u64>::is_prime(&bitset, n),
+                PrimalityTest::<u64>::is_prime(&trial, n),
+                "policies disagree on {}", n
+            );
+        }
+        assert!(PrimalityTest::<u32>::is_prime(&bitset, 199_999));
+        assert!(!PrimalityTest::<u16>::is_prime(&bitset, 65_535));
+    }
+
+    #[test]
+    fn test_is_prime_const_matches_sieve() {
+        assert_eq!(PRIME_SLOTS.len(), 31);
+        for n in 0..20_000u64 {
+            assert_eq!(is_prime_const(n), is_prime_sieve(n), "{}", n);
+        }
+        assert!(is_prime_const(4_294_967_291));
+        assert!(!is_prime_const(4_294_967_297));
+    }
+
+    #[test]
+    fn test_is_prime_cancellable() {
+        use crate::is_prime_cancellable;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::Duration;
+
+        let never = AtomicBool::new(false);
+        for n in 0..5_000u64 {
+            assert_eq!(is_prime_cancellable(n, &never), Some(is_prime_sieve(n)), "{}", n);
+        }
+        assert_eq!(is_prime_cancellable(1_000_000_007, &never), Some(true));
+
+        // 4294967291 × 4294967279: about 7 × 10^8 trial divisions to find the factor
+        let composite = 4_294_967_291u64 * 4_294_967_279;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let setter = {
+            let cancel = Arc::clone(&cancel);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+
+        // Finishing would take minutes and return Some(false); None means the
+        // loop noticed the flag and stopped
+        assert_eq!(is_prime_cancellable(composite, &cancel), None);
+        setter.join().unwrap();
+
+        // An already raised flag still lets the cheap cases through
+        assert_eq!(is_prime_cancellable(10, &cancel), Some(false));
+    }
+
+    #[test]
+    fn test_precomputed_sieve_counts() {
+        use crate::{prime_pi, PrecomputedSieve};
+
+        for limit in [0u64, 1, 2, 3, 127, 128, 129, 1_000, 65_536] {
+            let sieve = PrecomputedSieve::new(limit);
+            assert_eq!(sieve.count(), prime_pi(limit), "limit {}", limit);
+
+            // Word-level popcount against a naive per-number count
+            let mut naive = 0;
+            for n in 0..=limit {
+                assert_eq!(sieve.count_below(n), naive, "below {} with limit {}", n, limit);
+                if sieve.is_prime(n) == Some(true) {
+                    naive += 1;
+                }
+            }
+            assert_eq!(sieve.count_below(limit + 1), naive);
+            assert_eq!(sieve.is_prime(limit + 1), None);
+        }
+
+        let sieve = PrecomputedSieve::new(10_000_000);
+        assert_eq!(sieve.count(), 664_579);
+        assert_eq!(sieve.count_below(1_000_000), 78_498);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the sieved limit")]
+    fn test_precomputed_sieve_count_above_limit() {
+        crate::PrecomputedSieve::new(100).count_below(102);
+    }
+
+    #[test]
+    fn test_is_prime_sieve_instrumented() {
+        use crate::is_prime_sieve_instrumented;
+
+        for &p in &[101u64, 10_007, 1_000_003, 1_000_000_007] {
+            let (verdict, divisions) = is_prime_sieve_instrumented(p);
+            assert!(verdict);
+            // One division per odd candidate 3, 5, ..., ⌊√p⌋, about √p / 2
+            assert_eq!(divisions, (p.isqrt() - 1) / 2, "{}", p);
+            assert!((divisions as f64 - (p as f64).sqrt() / 2.0).abs() < 1.5, "{}", p);
+        }
+
+        assert_eq!(is_prime_sieve_instrumented(1_000_000), (false, 0));
+        assert_eq!(is_prime_sieve_instrumented(2), (true, 0));
+        assert_eq!(is_prime_sieve_instrumented(1), (false, 0));
+        // 15 falls to its first candidate, 3; 49 needs 3 and 5 before 7
+        assert_eq!(is_prime_sieve_instrumented(15), (false, 1));
+        assert_eq!(is_prime_sieve_instrumented(49), (false, 3));
+
+        for n in 0..2_000u64 {
+            assert_eq!(is_prime_sieve_instrumented(n).0, is_prime_sieve(n), "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_sieve_limit_is_integer_exact() {
+        use crate::algorithms::arith::trial_division_limit;
+
+        // u64::MAX rounds up to 2^64 as an f64, whose root overshot to 2^32 + 1
+        assert_eq!(trial_division_limit(u64::MAX), 1 << 32);
+        // (2^60 + 3)² rounds down far enough that the float root was 2^60,
+        // a limit of 2^60 + 1 that stops short of the factor 2^60 + 3
+        let root = (1u128 << 60) + 3;
+        assert_eq!(trial_division_limit(root * root), root + 1);
+        assert_eq!(trial_division_limit(u128::MAX), 1 << 64);
+        assert_eq!(trial_division_limit(u8::MAX), 16);
+
+        assert!(!is_prime_sieve(u64::MAX));
+        assert!(!is_prime_sieve(u64::MAX - 1));
+        assert!(!is_prime_sieve(u128::MAX));
+        assert!(is_prime_sieve(4_294_967_291u32));
+        assert!(!is_prime_sieve(65_521u32 * 65_521));
+    }
+
+    #[test]
+    fn test_primes_in_ranges() {
+        use crate::{primes_in_range, primes_in_ranges};
+
+        // Disjoint windows, given out of order
+        assert_eq!(primes_in_ranges(&[20..=30, 0..=10]), [2, 3, 5, 7, 23, 29]);
+        // Overlapping and nested windows list each prime once
+        assert_eq!(primes_in_ranges(&[0..=20, 10..=30, 12..=14]), primes_in_range(0..=30));
+        // Adjacent windows merge into one
+        assert_eq!(primes_in_ranges(&[0..=10, 11..=20]), primes_in_range(0..=20));
+
+        assert!(primes_in_ranges(&[]).is_empty());
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = [10..=5];
+        assert!(primes_in_ranges(&empty).is_empty());
+        let high = 1_000_000_000_000..=1_000_000_000_100;
+        assert_eq!(primes_in_ranges(&[high.clone(), 97..=97])[1..], primes_in_range(high));
+
+        let ranges = [1_000_000..=1_050_000, 1_020_000..=1_100_000, 5_000..=6_000, 1_099_000..=1_099_500];
+        let mut expected: Vec<u64> = ranges.iter().flat_map(|range| primes_in_range(range.clone())).collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(primes_in_ranges(&ranges), expected);
+    }
+
+    #[test]
+    fn test_sieve_near_2_pow_60() {
+        use crate::algorithms::arith::trial_division_limit;
+        use crate::{integer_sqrt, is_prime_miller_rabin};
+
+        // Perfect squares and their neighbours, where a float root is off by one
+        for root in [(1u64 << 30) - 35, 1 << 30, 999_999_999, 1_073_741_823] {
+            let square = root * root;
+            assert_eq!(trial_division_limit(square), root + 1);
+            assert_eq!(trial_division_limit(square - 1), root);
+            assert_eq!(integer_sqrt(square + 2 * root), root);
+            assert_eq!(integer_sqrt(square + 2 * root + 1), root + 1);
+        }
+
+        // Composites near 2^60 whose smallest factor is far past the small
+        // primes, and the squares of those factors
+        for p in [100_003u64, 999_983, 1_000_003] {
+            let q = crate::next_prime((1u64 << 60) / p).unwrap();
+            assert!(!is_prime_sieve(p * q), "{} × {}", p, q);
+            assert!(!is_prime_sieve(p * p), "{}²", p);
+        }
+        for n in (1u64 << 60) - 200..(1u64 << 60) {
+            if !is_prime_miller_rabin(n, 0) {
+                assert!(!is_prime_sieve(n), "{} is composite", n);
+            }
+        }
+
+        // The largest prime below 2^60 takes the scan all the way to 2^30
+        let largest = (1u64 << 60) - 93;
+        assert!(is_prime_miller_rabin(largest, 0));
+        assert!(is_prime_sieve(largest));
+    }
+}
+
+#[cfg(test)]
+mod miller_rabin_tests {
+    use crate::{
+        base_strength, is_prime_miller_rabin, is_prime_miller_rabin_extra, is_strong_probable_prime, RandomSource,
+        SplitMix64,
+    };
+
+    /// Random source that counts how many values were drawn from it
+    struct CountingRng {
+        inner: SplitMix64,
+        calls: u32,
+    }
+
+    impl RandomSource for CountingRng {
+        fn next_u64(&mut self) -> u64 {
+            self.calls += 1;
+            self.inner.next_u64()
+        }
+    }
+
+    #[test]
+    fn test_extra_random_bases_keep_primes() {
+        let primes = [5u64, 7, 97, 1_000_000_007, 18_446_744_073_709_551_557];
+        let mut rng = CountingRng { inner: SplitMix64::new(42), calls: 0 };
+
+        for &p in &primes {
+            rng.calls = 0;
+            assert!(is_prime_miller_rabin_extra(p, 16, &mut rng), "{} should be prime", p);
+            assert_eq!(rng.calls, 16, "all extra bases should be exercised for {}", p);
+        }
+    }
+
+    #[test]
+    fn test_extra_random_bases_reject_composites() {
+        let composites = [4u64, 9, 561, 1_000_000_000, 9_999_997_000_029_991];
+        let mut rng = SplitMix64::new(7);
+
+        for &n in &composites {
+            assert!(!is_prime_miller_rabin_extra(n, 8, &mut rng), "{} should be composite", n);
+        }
+    }
+
+    #[test]
+    fn test_extra_random_bases_above_u64() {
+        // 2^64 + 13 is prime; its bases span more than 64 bits, so each takes two draws
+        let p = (1u128 << 64) + 13;
+        let mut rng = CountingRng { inner: SplitMix64::new(42), calls: 0 };
+        assert!(is_prime_miller_rabin_extra(p, 16, &mut rng));
+        assert_eq!(rng.calls, 32);
+
+        // 2^64 + 1 = 274177 × 67280421310721
+        assert!(!is_prime_miller_rabin_extra((1u128 << 64) + 1, 16, &mut rng));
+    }
+
+    #[test]
+    fn test_base_strength() {
+        // Strong pseudoprimes to base 2 below 10^4: 2047, 3277, 4033, 4681, 8321
+        assert_eq!(base_strength(2, 10_000), 5);
+        for &n in &[2047u64, 3277, 4033, 4681, 8321] {
+            assert!(is_strong_probable_prime(n, 2), "{} is a base-2 strong pseudoprime", n);
+        }
+
+        // Base 15 is a better single witness in this range, base 16 = 2^4 a worse one
+        assert!(base_strength(15, 10_000) < base_strength(2, 10_000));
+        assert!(base_strength(16, 10_000) > base_strength(2, 10_000));
+    }
+
+    #[test]
+    fn test_configured_miller_rabin_clone() {
+        use crate::{MillerRabinAlgorithm, PrimalityTest};
+
+        // Base 2 alone is fooled by the strong pseudoprime 2047 = 23 × 89
+        let weak = MillerRabinAlgorithm::with_witnesses(vec![2]);
+        let clone = weak.clone();
+
+        assert_eq!(clone.witnesses(), &[2]);
+        for n in [2u64, 7, 2047, 2048, 1_000_000_007] {
+            assert_eq!(
+                PrimalityTest::<u64>::is_prime(&clone, n),
+                PrimalityTest::<u64>::is_prime(&weak, n),
+                "clone disagrees on {}", n
+            );
+        }
+        assert!(PrimalityTest::<u64>::is_prime(&clone, 2047));
+        assert!(!PrimalityTest::<u64>::is_prime(&MillerRabinAlgorithm::default(), 2047));
+        assert!(format!("{:?}", clone).contains("witnesses: [2]"));
+    }
+
+    #[test]
+    fn test_witness_skip_for_small_primes() {
+        use crate::{is_prime_miller_rabin, MillerRabinAlgorithm, PrimalityTest};
+
+        // Most deterministic witnesses are ≥ n here and get skipped
+        for &p in &[5u64, 7, 11, 13, 31, 37] {
+            assert!(is_prime_miller_rabin(p, 0), "{} should be prime", p);
+        }
+        for &n in &[9u64, 15, 21, 25, 27, 33, 35] {
+            assert!(!is_prime_miller_rabin(n, 0), "{} should be composite", n);
+        }
+
+        // A custom base equal to n is skipped instead of being reduced to 0
+        let equal_base = MillerRabinAlgorithm::with_witnesses(vec![11]);
+        assert!(PrimalityTest::<u64>::is_prime(&equal_base, 11));
+
+        // When every custom base is skipped, n is still actually tested
+        let too_large = MillerRabinAlgorithm::with_witnesses(vec![100]);
+        assert!(!PrimalityTest::<u64>::is_prime(&too_large, 9));
+        assert!(!PrimalityTest::<u64>::is_prime(&too_large, 91));
+        assert!(PrimalityTest::<u64>::is_prime(&too_large, 97));
+    }
+
+    #[test]
+    fn test_sorenson_webster_bases() {
+        use crate::{is_prime_miller_rabin, is_prime_sieve, Certainty, MillerRabinAlgorithm, PrimalityTest};
+
+        let sw = MillerRabinAlgorithm::sorenson_webster();
+        assert_eq!(sw.witnesses().len(), 7);
+        assert_eq!(MillerRabinAlgorithm::default().witnesses(), sw.witnesses());
+
+        // Small inputs, where most bases are ≥ n and must be reduced
+        for n in 0u64..20_000 {
+            assert_eq!(PrimalityTest::<u64>::is_prime(&sw, n), is_prime_sieve(n), "mismatch at {}", n);
+        }
+
+        // Differential test against the 12-base set over random inputs
+        let mut rng = SplitMix64::new(934);
+        for _ in 0..100_000 {
+            let n = rng.next_u64() | 1;
+            assert_eq!(PrimalityTest::<u64>::is_prime(&sw, n), is_prime_miller_rabin(n, 0), "mismatch at {}", n);
+        }
+
+        // Strong pseudoprimes to several prime bases
+        for n in [2047u64, 3_215_031_751, 341_550_071_728_321, 3_825_123_056_546_413_051] {
+            assert!(!is_prime_miller_rabin(n, 0));
+            assert_eq!(PrimalityTest::<u64>::is_prime(&sw, n), is_prime_miller_rabin(n, 0));
+        }
+
+        assert!(PrimalityTest::<u64>::is_prime(&sw, 18_446_744_073_709_551_557));
+        assert_eq!(PrimalityTest::<u64>::assess(&sw, 18_446_744_073_709_551_557).certainty, Certainty::Proven);
+        assert_eq!(sw.assess((1u128 << 89) - 1).certainty, Certainty::Probable);
+
+        // Above 2^64 the 12 prime bases take over, and are still a proof below ψ₁₂
+        let above = (1u128 << 64) + 13;
+        assert!(PrimalityTest::<u128>::is_prime(&sw, above));
+        assert!(!PrimalityTest::<u128>::is_prime(&sw, (1u128 << 64) + 1));
+        assert_eq!(sw.assess(above).certainty, Certainty::Proven);
+    }
+
+    #[test]
+    fn test_is_prime_any() {
+        use crate::is_prime_any;
+
+        assert!(is_prime_any(7i32));
+        assert!(is_prime_any(7usize));
+        assert!(is_prime_any(4_294_967_291u32));
+        assert!(is_prime_any(18_446_744_073_709_551_557u64));
+        assert!(!is_prime_any(-1i64));
+        assert!(!is_prime_any(-7i32));
+        assert!(!is_prime_any(9u8));
+        assert!(!is_prime_any(0i128));
+
+        // Does not fit in a u64, so it cannot be tested
+        assert!(!is_prime_any((1u128 << 89) - 1));
+    }
+
+    /// Seed of the regression snapshot; changing it invalidates the expected hash
+    const SNAPSHOT_SEED: u64 = 0x5eed_e7a7_0000_0001;
+
+    /// Verdicts on the snapshot inputs, folded with 64-bit FNV-1a
+    fn snapshot_hash(count: usize, max_n: u64) -> (u64, usize) {
+        let mut rng = SplitMix64::new(SNAPSHOT_SEED);
+        let mut hash = 0xcbf2_9ce4_8422_2325u64;
+        let mut primes = 0;
+        for _ in 0..count {
+            let n = rng.next_u64() % (max_n + 1);
+            let verdict = is_prime_miller_rabin(n, 0);
+            primes += verdict as usize;
+            hash ^= verdict as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        (hash, primes)
+    }
+
+    #[test]
+    fn test_regression_snapshot() {
+        // Computed once from the current implementation; any change to the
+        // verdicts on these 10,000 inputs changes the hash
+        let (hash, primes) = snapshot_hash(10_000, 1_000_000_000_000);
+        assert_eq!(primes, 372);
+        assert_eq!(hash, 0xb5a7_5fe2_6e70_e6f7, "hash {:#018x}", hash);
+    }
+
+    #[test]
+    fn test_fermat_prefilter_differential() {
+        use crate::{MillerRabinAlgorithm, PrimalityTest};
+
+        let plain = MillerRabinAlgorithm::default();
+        let filtered = MillerRabinAlgorithm::default().with_fermat_prefilter(true);
+        let filtered_sw = MillerRabinAlgorithm::sorenson_webster().with_fermat_prefilter(true);
+        let check = |n: u64| {
+            let expected = PrimalityTest::<u64>::is_prime(&plain, n);
+            assert_eq!(PrimalityTest::<u64>::is_prime(&filtered, n), expected, "n = {}", n);
+            assert_eq!(PrimalityTest::<u64>::is_prime(&filtered_sw, n), expected, "n = {}", n);
+            assert_eq!(PrimalityTest::<u64>::assess(&filtered, n), PrimalityTest::<u64>::assess(&plain, n));
+        };
+
+        (0..50_000).for_each(check);
+        // Base-2 Fermat pseudoprimes pass the filter and must be caught later
+        [341, 561, 645, 1105, 1387, 1729, 1905, 2047, 2465, 3_215_031_751].into_iter().for_each(check);
+        let mut rng = SplitMix64::new(978);
+        (0..10_000).map(|_| rng.next_u64()).for_each(check);
+
+        // Narrow and wide types take the same route
+        assert!(PrimalityTest::<u32>::is_prime(&filtered, 4_294_967_291));
+        assert!(!PrimalityTest::<u128>::is_prime(&filtered, 3_215_031_751));
+    }
+
+    #[test]
+    fn test_every_small_odd_n_runs_a_witness() {
+        use crate::algorithms::miller_rabin::applicable_bases;
+        use crate::DETERMINISTIC_WITNESSES;
+
+        // Guards the witness list: if a change left some small odd n with no
+        // base below it, the test would fall back instead of running the set
+        for n in (5..50u64).step_by(2) {
+            let bases: Vec<u64> = applicable_bases(n, &DETERMINISTIC_WITNESSES, false).collect();
+            assert!(!bases.is_empty(), "no deterministic witness runs on {}", n);
+            assert!(bases.iter().all(|&a| (2..n).contains(&a)), "n = {}: {:?}", n, bases);
+            assert_eq!(bases, DETERMINISTIC_WITNESSES.iter().copied().filter(|&a| a < n).collect::<Vec<_>>());
+
+            // The verdict is the one the applicable bases give
+            let all_pass = bases.iter().all(|&a| is_strong_probable_prime(n, a));
+            assert_eq!(is_prime_miller_rabin(n, 0), all_pass, "n = {}", n);
+            assert_eq!(all_pass, (2..n).all(|d| !n.is_multiple_of(d)), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_miller_rabin_u128_above_u64() {
+        use crate::{ModArith, MillerRabinAlgorithm, PrimalityTest, LARGEST_U64_PRIME};
+
+        // Operands close to 2^128 go through the double-and-add mul_mod
+        for p in [(1u128 << 64) + 13, (1 << 89) - 1, (1 << 127) - 1, u128::MAX - 158] {
+            assert!(is_prime_miller_rabin(p, 0), "{} is prime", p);
+            assert!(PrimalityTest::<u128>::is_prime(&MillerRabinAlgorithm::default(), p));
+        }
+
+        let big = LARGEST_U64_PRIME as u128;
+        for n in [big * big, big * ((1 << 64) + 13), ((1 << 89) - 1) * ((1 << 31) - 1), u128::MAX] {
+            assert!(!is_prime_miller_rabin(n, 0), "{} is composite", n);
+        }
+
+        // Chernick Carmichael numbers (6k + 1)(12k + 1)(18k + 1) for k = 242396 and
+        // 242420: they pass the Fermat test to every coprime base, but not Miller-Rabin
+        for (a, b, c) in [(1_454_377u128, 2_908_753, 4_363_129), (1_454_521, 2_909_041, 4_363_561)] {
+            let n = a * b * c;
+            assert!(n > u64::MAX as u128);
+            assert!(is_prime_miller_rabin(a, 0) && is_prime_miller_rabin(b, 0) && is_prime_miller_rabin(c, 0));
+            for base in [2u128, 3, 5, 7, 11] {
+                assert_eq!(base.pow_mod(n - 1, n), 1, "{}^(n-1) mod {}", base, n);
+            }
+            assert!(!is_prime_miller_rabin(n, 0), "{} is composite", n);
+        }
+    }
+}
+
+#[cfg(test)]
+mod counting_tests {
+    use crate::{
+        is_prime_miller_rabin, local_prime_density, mertens, mobius, mobius_sieve, prime_count_with_bounds, prime_pi,
+        prime_pi_legendre, prime_pi_riemann, primes_up_to, riemann_r, PRIME_PI_AT_POWERS_OF_TEN,
+    };
+
+    #[test]
+    fn test_primes_up_to() {
+        assert!(primes_up_to(1).is_empty());
+        assert_eq!(primes_up_to(2), vec![2]);
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+        let expected: Vec<u64> = (0..=10_000).filter(|&n| is_prime_miller_rabin(n, 0)).collect();
+        assert_eq!(primes_up_to(10_000), expected);
+    }
+
+    #[test]
+    fn test_prime_pi_known_values() {
+        let known = [(0, 0), (1, 0), (2, 1), (10, 4), (100, 25), (1000, 168), (10_000, 1229), (1_000_000, 78_498)];
+
+        for &(x, pi) in &known {
+            assert_eq!(prime_pi(x), pi, "pi({}) should be {}", x, pi);
+        }
+    }
+
+    #[test]
+    fn test_prime_count_with_bounds() {
+        for &x in &[1000u64, 10_000, 100_000, 1_000_000] {
+            let (exact, r, li) = prime_count_with_bounds(x);
+            assert_eq!(exact, prime_pi(x));
+
+            let r_error = (r - exact as f64).abs();
+            let li_error = (li - exact as f64).abs();
+            assert!(
+                r_error < li_error,
+                "R({}) = {} should be closer to pi = {} than li = {}",
+                x, r, exact, li
+            );
+        }
+
+        // Sanity check against published values: li(10^6) ≈ 78627.5, R(10^6) ≈ 78527.4
+        let (_, r, li) = prime_count_with_bounds(1_000_000);
+        assert!((li - 78_627.5).abs() < 1.0, "li(10^6) = {}", li);
+        assert!((r - 78_527.4).abs() < 1.0, "R(10^6) = {}", r);
+    }
+
+    #[test]
+    fn test_local_prime_density() {
+        assert!((local_prime_density(15, 5) - 4.0 / 11.0).abs() < 1e-12); // 11, 13, 17, 19 in [10, 20]
+
+        let center = 1_000_000u64;
+        let window = 10_000u64;
+        let density = local_prime_density(center, window);
+        let expected = 1.0 / (center as f64).ln();
+
+        // Allow three standard deviations of the (roughly Poisson) prime count
+        let len = (2 * window + 1) as f64;
+        let tolerance = 3.0 * (expected * len).sqrt() / len;
+        assert!(
+            (density - expected).abs() < tolerance,
+            "density {} should be within {} of {}",
+            density, tolerance, expected
+        );
+    }
+
+    #[test]
+    fn test_pi_inverse() {
+        use crate::pi_inverse;
+
+        // pi(100) = 25, and pi_inverse lands on the 25th prime, 97
+        assert!(pi_inverse(25).abs_diff(100) <= 5);
+        assert_eq!(prime_pi(pi_inverse(25)), 25);
+
+        assert_eq!(pi_inverse(0), 0);
+        assert_eq!(pi_inverse(1), 2);
+        assert_eq!(pi_inverse(5), 11);
+        assert_eq!(pi_inverse(6), 13);
+        assert_eq!(pi_inverse(1_000), 7_919);
+        assert_eq!(pi_inverse(78_498), 999_983);
+    }
+
+    #[test]
+    fn test_prime_pi_powers_of_ten_table() {
+        use crate::{verify_pi_against_known, PRIME_PI_AT_POWERS_OF_TEN};
+
+        assert_eq!(prime_pi(1_000_000), PRIME_PI_AT_POWERS_OF_TEN[6]);
+        assert_eq!(PRIME_PI_AT_POWERS_OF_TEN[6], 78_498);
+        assert_eq!(prime_pi(10_000_000), 664_579);
+        assert_eq!(PRIME_PI_AT_POWERS_OF_TEN[7], 664_579);
+        assert!(verify_pi_against_known());
+    }
+
+    #[test]
+    fn test_prime_count_chart_points() {
+        use crate::{prime_count_data, prime_count_series};
+
+        for max_n in [2, 30, 1_000, 1_005, 20_000, 20_050] {
+            let series = prime_count_series(max_n);
+            let (xs, ys) = (series.xs(), series.ys());
+            assert_eq!(xs.len(), ys.len());
+            assert_eq!(xs.last(), Some(&max_n));
+            for (&x, &y) in xs.iter().zip(&ys) {
+                assert_eq!(y, prime_pi(x), "pi({})", x);
+            }
+
+            // Same points as the interleaved format, de-interleaved
+            let flat = prime_count_data(max_n);
+            assert_eq!(flat.iter().step_by(2).copied().collect::<Vec<_>>(), xs);
+            assert_eq!(flat.iter().skip(1).step_by(2).copied().collect::<Vec<_>>(), ys);
+        }
+
+        assert_eq!(prime_count_series(30).xs().len(), 29);
+        assert_eq!(prime_count_series(20_050).xs()[..3], [2, 22, 42]);
+        assert_eq!(prime_count_series(2).xs(), [2]);
+        assert!(prime_count_series(1).xs().is_empty() && prime_count_series(0).ys().is_empty());
+    }
+
+    #[test]
+    fn test_prime_count_chart_resolution_is_smooth() {
+        use crate::{prime_count_data, prime_count_series};
+
+        // Neighbouring bounds used to fall on either side of a step change
+        for boundary in [1_000u64, 10_000] {
+            let below = prime_count_series(boundary - 1).xs().len() as f64;
+            let above = prime_count_series(boundary + 1).xs().len() as f64;
+            assert!((below / above - 1.0).abs() < 0.01, "{} vs {} points around {}", below, above, boundary);
+        }
+        assert_eq!(prime_count_data(9_999).len(), prime_count_data(10_001).len());
+
+        let mut previous = 0;
+        for max_n in (2..3_000).chain([100_000, 1 << 20]) {
+            let xs = prime_count_series(max_n).xs();
+            assert!(xs.len() >= previous && xs.len() <= 1000, "max_n = {}", max_n);
+            assert!(xs.windows(2).all(|pair| pair[0] < pair[1]), "max_n = {}", max_n);
+            assert_eq!((xs[0], xs[xs.len() - 1]), (2, max_n));
+            previous = xs.len();
+        }
+    }
+
+    #[test]
+    fn test_mertens() {
+        assert_eq!(mertens(0), 0);
+        assert_eq!(mertens(1), 1);
+        assert_eq!(mertens(10), -1);
+        assert_eq!(mertens(100), 1);
+        assert_eq!(mertens(1_000), 2);
+        assert_eq!(mertens(10_000), -23);
+        assert_eq!(mertens(1_000_000), 212);
+
+        // Running sum of μ computed from the factorization of each k
+        let mut sum = 0;
+        for k in 1..=500u64 {
+            sum += mobius(k) as i64;
+            assert_eq!(mertens(k), sum, "M({})", k);
+        }
+    }
+
+    #[test]
+    fn test_mobius() {
+        assert_eq!(mobius(1), 1);
+        assert_eq!(mobius(2), -1);
+        assert_eq!(mobius(6), 1);
+        assert_eq!(mobius(12), 0);
+        assert_eq!(mobius(30), -1);
+        assert_eq!(mobius(0), 0);
+        assert_eq!(mobius(18_446_744_073_709_551_557), -1);
+
+        let sieved = mobius_sieve(10_000);
+        assert_eq!(sieved.len(), 10_001);
+        for (k, &mu) in sieved.iter().enumerate() {
+            assert_eq!(mu, mobius(k as u64), "μ({})", k);
+        }
+        assert_eq!(mobius_sieve(0), [0]);
+        assert_eq!(mobius_sieve(1), [0, 1]);
+    }
+
+    #[test]
+    fn test_prime_pi_legendre() {
+        for x in 0..3_000 {
+            assert_eq!(prime_pi_legendre(x), prime_pi(x), "pi({})", x);
+        }
+        for x in [65_535, 1_000_003, 12_345_678, 100_000_000] {
+            assert_eq!(prime_pi_legendre(x), prime_pi(x), "pi({})", x);
+        }
+        for (k, &expected) in PRIME_PI_AT_POWERS_OF_TEN.iter().enumerate().take(11) {
+            assert_eq!(prime_pi_legendre(10u64.pow(k as u32)), expected, "pi(10^{})", k);
+        }
+    }
+
+    #[test]
+    fn test_prime_pi_riemann() {
+        for exponent in 4..=6 {
+            let x = 10f64.powi(exponent);
+            let exact = PRIME_PI_AT_POWERS_OF_TEN[exponent as usize] as f64;
+            let riemann = prime_pi_riemann(x);
+            let li = crate::logarithmic_integral(x);
+
+            assert!((riemann - exact).abs() < (li - exact).abs(), "x = {}: R = {}, li = {}", x, riemann, li);
+            assert!((riemann - riemann_r(x)).abs() < 0.5, "x = {}: Möbius and Gram series disagree", x);
+        }
+    }
+
+    #[test]
+    fn test_liouville() {
+        use crate::{big_omega, liouville, liouville_sum};
+
+        assert_eq!(liouville(0), 0);
+        assert_eq!(liouville(1), 1);
+        assert_eq!(liouville(2), -1);
+        assert_eq!(liouville(4), 1);
+        assert_eq!(liouville(6), 1);
+        assert_eq!(liouville(12), -1);
+        assert_eq!(big_omega(1), 0);
+        assert_eq!(big_omega(360), 6);
+        assert_eq!(big_omega(1 << 63), 63);
+
+        // λ and μ agree on squarefree numbers
+        for k in 1..=1_000u64 {
+            if mobius(k) != 0 {
+                assert_eq!(liouville(k), mobius(k), "λ({})", k);
+            }
+        }
+
+        // Running sum of λ computed from the factorization of each k
+        assert_eq!(liouville_sum(0), 0);
+        let mut sum = 0;
+        for k in 1..=2_000u64 {
+            sum += liouville(k) as i64;
+            assert_eq!(liouville_sum(k), sum, "L({})", k);
+        }
+        // Pólya's conjecture holds far beyond this range
+        assert!((2..=2_000).all(|k| liouville_sum(k) <= 0));
+        assert_eq!(liouville_sum(1_000_000), -530);
+    }
+}
+
+#[cfg(test)]
+mod primes_tests {
+    use crate::{is_prime_miller_rabin, next_prime, primes_up_to, PrimeIterator, WindowedPrimes, LARGEST_U64_PRIME};
+
+    #[test]
+    fn test_prime_iterator_matches_sieve() {
+        let expected = primes_up_to(300_000);
+        let generated: Vec<u64> = PrimeIterator::new().take(expected.len()).collect();
+        assert_eq!(generated, expected);
+    }
+
+    #[test]
+    fn test_windowed_primes_recent() {
+        let mut primes = WindowedPrimes::new(3);
+        assert!(primes.is_empty());
+
+        for _ in 0..5 {
+            primes.next_prime();
+        }
+
+        assert_eq!(primes.len(), 3);
+        assert_eq!(primes.recent(2).collect::<Vec<_>>(), vec![7, 11]);
+        assert_eq!(primes.recent(10).collect::<Vec<_>>(), vec![5, 7, 11]);
+    }
+
+    #[test]
+    fn test_windowed_primes_maximal_gap() {
+        // The largest gap among the first 10^6 primes is 154, after 4652353
+        let mut primes = WindowedPrimes::new(2);
+        let mut max_gap = (0, 0);
+
+        primes.next_prime();
+        for _ in 1..1_000_000 {
+            primes.next_prime();
+            let pair: Vec<u64> = primes.recent(2).collect();
+            let gap = pair[1] - pair[0];
+            if gap > max_gap.0 {
+                max_gap = (gap, pair[0]);
+            }
+            assert!(primes.len() <= 2);
+        }
+
+        assert_eq!(max_gap, (154, 4_652_353));
+    }
+
+    #[test]
+    fn test_prime_generation_small_limit() {
+        let limit = 50;
+        let expected = vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+        assert_eq!(primes_up_to(limit), expected);
+        assert_eq!(PrimeIterator::new().take_while(|&p| p <= limit).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_range_primes_extension() {
+        use crate::{primes_in_range, RangePrimes};
+
+        assert_eq!((2..=30).primes().collect::<Vec<_>>(), primes_up_to(30));
+        assert_eq!((2..30).primes().collect::<Vec<_>>(), primes_up_to(29));
+        assert_eq!((24..=28).primes().count(), 0);
+        let high = 29u64;
+        assert_eq!((30..=high).primes().count(), 0);
+        assert_eq!((5..5).primes().count(), 0);
+
+        // Spans several segments and matches the eager sieve
+        assert_eq!((100_000u64..=400_000).primes().collect::<Vec<_>>(), primes_in_range(100_000..=400_000));
+
+        // Laziness: only the first segment is needed here
+        assert_eq!((1_000_000u64..=u64::MAX >> 24).primes().next(), Some(1_000_003));
+        // The base primes grow per segment instead of reaching √u64::MAX up front
+        assert_eq!((0..=u64::MAX).primes().take(5).collect::<Vec<_>>(), [2, 3, 5, 7, 11]);
+    }
+
+    #[test]
+    fn test_primes_until() {
+        use crate::primes_until;
+
+        assert_eq!(primes_until(|p| p > 50), primes_up_to(50));
+        assert!(primes_until(|_| true).is_empty());
+
+        // Stateful predicate: stop once the running sum would exceed 100
+        let mut sum = 0;
+        let primes = primes_until(|p| {
+            sum += p;
+            sum > 100
+        });
+        assert_eq!(primes, [2, 3, 5, 7, 11, 13, 17, 19, 23]);
+    }
+
+    #[test]
+    fn test_wheel() {
+        use crate::Wheel;
+
+        let wheel = Wheel::new(&[2, 3, 5]);
+        assert_eq!(wheel.modulus(), 30);
+        assert_eq!(wheel.spokes(), [1, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(
+            wheel.candidates().take(12).collect::<Vec<_>>(),
+            [7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]
+        );
+
+        // Every prime above the wheel primes is a candidate
+        let candidates: Vec<u64> = wheel.candidates().take_while(|&c| c <= 10_000).collect();
+        let primes: Vec<u64> = primes_up_to(10_000).into_iter().skip(3).collect();
+        assert!(primes.iter().all(|p| candidates.binary_search(p).is_ok()));
+        assert_eq!(candidates.len(), 10_000 * 8 / 30 - 1);
+
+        assert_eq!(Wheel::new(&[2, 3, 5, 7]).spokes().len(), 48);
+        assert_eq!(Wheel::new(&[]).candidates().take(3).collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(Wheel::new(&[2]).candidates().take(3).collect::<Vec<_>>(), [3, 5, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "wheel primes must be at least 2, got 1")]
+    fn test_wheel_rejects_one() {
+        crate::Wheel::new(&[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "wheel primes must be at least 2, got 0")]
+    fn test_wheel_rejects_zero() {
+        crate::Wheel::new(&[2, 0]);
+    }
+
+    #[test]
+    fn test_bounded_prime_iterator_both_ends() {
+        use crate::{BoundedPrimeIterator, RangePrimes};
+
+        let forward: Vec<u64> = BoundedPrimeIterator::new(30).collect();
+        let mut reverse: Vec<u64> = BoundedPrimeIterator::new(30).rev().collect();
+        assert_eq!(forward, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        reverse.reverse();
+        assert_eq!(reverse, forward);
+        assert_eq!(BoundedPrimeIterator::new(30).next_back(), Some(29));
+        assert_eq!(BoundedPrimeIterator::new(1).next_back(), None);
+
+        // Alternating ends across several segments meet without losing or repeating primes
+        let mut both = BoundedPrimeIterator::new(300_000);
+        let mut seen = Vec::new();
+        loop {
+            match (both.next(), both.next_back()) {
+                (None, None) => break,
+                (low, high) => seen.extend(low.into_iter().chain(high)),
+            }
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, primes_up_to(300_000));
+
+        // Largest primes below a huge bound, without sieving anything below it
+        let top: Vec<u64> = (0..=1 << 40).primes().rev().take(2).collect();
+        assert_eq!(top, [1_099_511_627_689, 1_099_511_627_609]);
+    }
+
+    #[test]
+    fn test_prime_iteration_stops_at_u64_max() {
+        let mut top = PrimeIterator::starting_at(LARGEST_U64_PRIME);
+        assert_eq!(top.next(), Some(LARGEST_U64_PRIME));
+        assert_eq!(top.next(), None);
+        assert_eq!(top.next(), None);
+        assert_eq!(PrimeIterator::starting_at(u64::MAX).next(), None);
+
+        // The last primes below u64::MAX, found by Miller-Rabin past 2^44
+        let last: Vec<u64> = PrimeIterator::starting_at(u64::MAX - 200).collect();
+        let expected: Vec<u64> = (u64::MAX - 200..=u64::MAX).filter(|&n| is_prime_miller_rabin(n, 0)).collect();
+        assert_eq!(last, expected);
+        assert_eq!(last.last(), Some(&LARGEST_U64_PRIME));
+
+        assert_eq!(next_prime(LARGEST_U64_PRIME - 1), Some(LARGEST_U64_PRIME));
+        assert_eq!(next_prime(LARGEST_U64_PRIME), None);
+        assert_eq!(next_prime(u64::MAX), None);
+        assert_eq!(next_prime(0), Some(2));
+        assert_eq!(next_prime(2), Some(3));
+        assert_eq!(next_prime(13), Some(17));
+
+        // Starting points agree with the unbounded iteration from 2
+        let from_two: Vec<u64> = PrimeIterator::new().take_while(|&p| p < 200_000).collect();
+        let from_mid: Vec<u64> = PrimeIterator::starting_at(100_000).take_while(|&p| p < 200_000).collect();
+        assert_eq!(from_mid, from_two.iter().copied().filter(|&p| p >= 100_000).collect::<Vec<_>>());
+        // The first segment is sieved and the second tested with Miller-Rabin
+        let (low, high) = ((1u64 << 44) - (1 << 16) - 3_000, (1u64 << 44) + 3_000);
+        let around_switch: Vec<u64> = PrimeIterator::starting_at(low).take_while(|&p| p < high).collect();
+        let expected: Vec<u64> = (low..high).filter(|&n| is_prime_miller_rabin(n, 0)).collect();
+        assert_eq!(around_switch, expected);
+    }
+}
+
+#[cfg(test)]
+mod arith_tests {
+    use crate::{
+        bit_length, decimal_digits, is_perfect_power, mod_sqrt, ModArith, Montgomery, RandomSource, SplitMix64,
+    };
+
+    /// Reference (a × b) mod m for u128 via a 256-bit schoolbook product
+    /// followed by bitwise long division
+    fn mul_mod_reference(a: u128, b: u128, m: u128) -> u128 {
+        let limbs = |x: u128| [x as u64, (x >> 64) as u64];
+        let (a, b) = (limbs(a), limbs(b));
+
+        let mut product = [0u64; 4];
+        for i in 0..2 {
+            let mut carry = 0u128;
+            for j in 0..2 {
+                let current = product[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+                product[i + j] = current as u64;
+                carry = current >> 64;
+            }
+            product[i + 2] = carry as u64;
+        }
+
+        let mut remainder = 0u128;
+        for bit in (0..256).rev() {
+            let next_bit = (product[bit / 64] >> (bit % 64)) & 1;
+            let overflow = remainder >> 127;
+            remainder = (remainder << 1) | next_bit as u128;
+            if overflow == 1 || remainder >= m {
+                remainder = remainder.wrapping_sub(m);
+            }
+        }
+        remainder
+    }
+
+    fn pow_mod_reference(base: u128, mut exp: u128, m: u128) -> u128 {
+        let mut result = 1 % m;
+        let mut base = base % m;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = mul_mod_reference(result, base, m);
+            }
+            base = mul_mod_reference(base, base, m);
+            exp >>= 1;
+        }
+        result
+    }
+
+    fn random_u128(rng: &mut SplitMix64) -> u128 {
+        ((rng.next_u64() as u128) << 64) | rng.next_u64() as u128
+    }
+
+    #[test]
+    fn test_mod_arith_u64() {
+        let mut rng = SplitMix64::new(1);
+
+        for _ in 0..1000 {
+            let m = rng.next_u64() | 1;
+            let a = rng.next_u64() % m;
+            let b = rng.next_u64() % m;
+            let (a_w, b_w, m_w) = (a as u128, b as u128, m as u128);
+
+            assert_eq!(a.add_mod(b, m) as u128, (a_w + b_w) % m_w);
+            assert_eq!(a.sub_mod(b, m) as u128, (a_w + m_w - b_w) % m_w);
+            assert_eq!(a.mul_mod(b, m) as u128, (a_w * b_w) % m_w);
+            assert_eq!(a.pow_mod(b, m) as u128, pow_mod_reference(a_w, b_w, m_w));
+        }
+
+        assert_eq!(0u64.sub_mod(1, u64::MAX), u64::MAX - 1);
+        assert_eq!((u64::MAX - 1).add_mod(u64::MAX - 1, u64::MAX), u64::MAX - 2);
+    }
+
+    #[test]
+    fn test_mod_arith_u128() {
+        let mut rng = SplitMix64::new(2);
+
+        for _ in 0..200 {
+            let m = random_u128(&mut rng) | 1 | (1 << 127);
+            let a = random_u128(&mut rng) % m;
+            let b = random_u128(&mut rng) % m;
+
+            let expected_add = if a >= m - b { a - (m - b) } else { a + b };
+            let expected_sub = if a >= b { a - b } else { m - (b - a) };
+
+            assert_eq!(a.add_mod(b, m), expected_add);
+            assert_eq!(a.sub_mod(b, m), expected_sub);
+            assert_eq!(a.mul_mod(b, m), mul_mod_reference(a, b, m));
+            assert_eq!(a.pow_mod(b, m), pow_mod_reference(a, b, m));
+        }
+    }
+
+    #[cfg(feature = "overflow_safe")]
+    #[test]
+    #[should_panic(expected = "overflow in add_mod: operands must be reduced modulo m")]
+    fn test_overflow_safe_unreduced_add_mod() {
+        // Without the feature this silently wraps to a wrong residue in release builds
+        let _ = 5u64.add_mod(u64::MAX, 7);
+    }
+
+    #[cfg(feature = "overflow_safe")]
+    #[test]
+    fn test_overflow_safe_mul_mod_extremes() {
+        // Neither multiplication path can overflow, so the feature never fires here
+        let m = u64::MAX - 58;
+        assert_eq!(u64::MAX.mul_mod(u64::MAX, m), 58 * 58);
+        assert_eq!(u64::MAX.pow_mod(3, m), 58 * 58 * 58);
+
+        let wide = u128::MAX - 158;
+        assert_eq!(u128::MAX.mul_mod(u128::MAX, wide), 158 * 158);
+    }
+
+    #[test]
+    fn test_checked_affine() {
+        use crate::checked_affine;
+
+        assert_eq!(checked_affine(11, 2, 1), Some(23));
+        assert_eq!(checked_affine(0, 5, 7), Some(7));
+        assert_eq!(checked_affine(7, 0, 0), Some(0));
+        assert_eq!(checked_affine(u64::MAX / 2, 2, 1), Some(u64::MAX));
+
+        // 2p + 1 overflows just above u64::MAX / 2, in the product or the sum
+        assert_eq!(checked_affine(u64::MAX / 2 + 1, 2, 1), None);
+        assert_eq!(checked_affine(u64::MAX / 2, 2, 2), None);
+        assert_eq!(checked_affine(u64::MAX, 1, 1), None);
+    }
+
+    #[test]
+    fn test_mod_sqrt() {
+        // 10 is a residue mod 13 (6² = 36 ≡ 10), 5 is not
+        assert_eq!(mod_sqrt(10, 13), Some(6));
+        assert_eq!(mod_sqrt(5, 13), None);
+        assert_eq!(mod_sqrt(0, 13), Some(0));
+        assert_eq!(mod_sqrt(1, 2), Some(1));
+
+        // Invalid moduli are rejected instead of searching forever for a non-residue
+        assert_eq!(mod_sqrt(1, 9), None);
+        assert_eq!(mod_sqrt(4, 25), None);
+        assert_eq!(mod_sqrt(1, 0), None);
+        assert_eq!(mod_sqrt(1, 4), None);
+        assert_eq!(mod_sqrt(1, 1), None);
+
+        // p ≡ 1 (mod 8) exercises the Tonelli-Shanks loop beyond the first step
+        for p in [17u64, 41, 97, 113, 1_000_000_009, 18_446_744_073_709_551_557] {
+            for a in 1..200u64 {
+                match mod_sqrt(a, p) {
+                    Some(r) => {
+                        assert_eq!(r.mul_mod(r, p), a % p, "root of {} mod {}", a, p);
+                        assert!(r <= p - r);
+                    }
+                    None if p < 1000 => assert!((1..p).all(|x| x.mul_mod(x, p) != a % p)),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_sqrt() {
+        use crate::integer_sqrt;
+
+        for n in 0..=u8::MAX {
+            let r = integer_sqrt(n) as u32;
+            assert!(r * r <= n as u32 && (r + 1) * (r + 1) > n as u32, "isqrt({})", n);
+        }
+        for n in (0..=u16::MAX).step_by(7) {
+            assert_eq!(integer_sqrt(n), n.isqrt());
+        }
+        for n in [u64::MAX, u64::MAX - 1, 1 << 63, 4_294_967_291 * 4_294_967_291] {
+            assert_eq!(integer_sqrt(n), n.isqrt(), "isqrt({})", n);
+        }
+        for n in [u128::MAX, 1 << 127, ((1u128 << 60) + 3).pow(2), ((1u128 << 60) + 3).pow(2) - 1] {
+            assert_eq!(integer_sqrt(n), n.isqrt(), "isqrt({})", n);
+        }
+    }
+
+    #[test]
+    fn test_is_perfect_power() {
+        assert_eq!(is_perfect_power(8), Some((2, 3)));
+        assert_eq!(is_perfect_power(64), Some((2, 6)));
+        assert_eq!(is_perfect_power(36), Some((6, 2)));
+        assert_eq!(is_perfect_power(1 << 63), Some((2, 63)));
+        assert_eq!(is_perfect_power(4_294_967_295 * 4_294_967_295), Some((4_294_967_295, 2)));
+        assert_eq!(is_perfect_power(3u64.pow(40)), Some((3, 40)));
+        for n in [0, 1, 2, 3, 12, 63, 65, u64::MAX] {
+            assert_eq!(is_perfect_power(n), None, "n = {}", n);
+        }
+
+        for n in 0..=10_000u64 {
+            let expected = (2..=100u64).any(|b| (2..=13).any(|k| b.checked_pow(k) == Some(n)));
+            assert_eq!(is_perfect_power(n).is_some(), expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_bit_length_and_decimal_digits() {
+        assert_eq!(bit_length(255), 8);
+        assert_eq!(bit_length(256), 9);
+        assert_eq!(bit_length(0), 0);
+        assert_eq!(bit_length(1), 1);
+        assert_eq!(bit_length(u64::MAX), 64);
+
+        assert_eq!(decimal_digits(999), 3);
+        assert_eq!(decimal_digits(1000), 4);
+        assert_eq!(decimal_digits(0), 1);
+        assert_eq!(decimal_digits(u64::MAX), 20);
+        for n in (0..10_000u64).chain([1_000_000_007, u64::MAX - 1]) {
+            assert_eq!(decimal_digits(n) as usize, n.to_string().len(), "n = {}", n);
+            assert_eq!(bit_length(n) as usize, format!("{:b}", n).trim_start_matches('0').len(), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_montgomery_matches_naive_pow_mod() {
+        let mut rng = SplitMix64::new(1001);
+        let moduli = (0..500).map(|_| rng.next_u64() | 1).chain([1, 3, crate::LARGEST_U64_PRIME, u64::MAX]);
+        let moduli: Vec<u64> = moduli.collect();
+
+        for m in moduli {
+            let montgomery = Montgomery::new(m).unwrap();
+            assert_eq!(montgomery.modulus(), m);
+            for _ in 0..20 {
+                let (base, exp) = (rng.next_u64(), rng.next_u64());
+                assert_eq!(montgomery.pow_mod(base, exp), base.pow_mod(exp, m), "{}^{} mod {}", base, exp, m);
+
+                let (a, b) = (rng.next_u64() % m, rng.next_u64() % m);
+                let product = montgomery.mul(montgomery.to_montgomery(a), montgomery.to_montgomery(b));
+                assert_eq!(montgomery.from_montgomery(product), a.mul_mod(b, m));
+            }
+            assert_eq!(montgomery.pow_mod(0, 0), 1 % m);
+        }
+
+        assert_eq!(Montgomery::new(0), None);
+        assert_eq!(Montgomery::new(1 << 40), None);
+    }
+}
+
+#[cfg(test)]
+mod special_forms_tests {
+    use crate::{is_prime_miller_rabin, is_proth_prime, jacobi, twin_prime_near};
+
+    #[test]
+    fn test_jacobi_symbol() {
+        // Quadratic residues modulo 7 are 1, 2 and 4
+        let expected = [0i8, 1, 1, -1, 1, -1, -1];
+        for (a, &symbol) in expected.iter().enumerate() {
+            assert_eq!(jacobi(a as u64, 7), symbol, "(a / 7) for a = {}", a);
+        }
+        assert_eq!(jacobi(2u64, 15), 1);
+        assert_eq!(jacobi(7u64, 15), -1);
+        assert_eq!(jacobi(5u64, 15), 0);
+    }
+
+    #[test]
+    fn test_proth_primes() {
+        // 3 = 1·2 + 1, 5 = 1·4 + 1, 13 = 3·4 + 1, 97 = 3·32 + 1, 193 = 3·64 + 1
+        for &(k, n) in &[(1u64, 1u32), (1, 2), (3, 2), (3, 5), (3, 6), (1, 16)] {
+            assert!(is_proth_prime(k, n), "{}·2^{} + 1 should be prime", k, n);
+        }
+
+        // 9 = 1·8 + 1, 25 = 3·8 + 1, 65 = 1·64 + 1
+        for &(k, n) in &[(1u64, 3u32), (3, 3), (1, 6)] {
+            assert!(!is_proth_prime(k, n), "{}·2^{} + 1 should be composite", k, n);
+        }
+
+        // Not Proth numbers: even k, k ≥ 2^n
+        assert!(!is_proth_prime(2, 3));
+        assert!(!is_proth_prime(9, 3));
+    }
+
+    #[test]
+    fn test_proth_matches_miller_rabin() {
+        for n in 1..20u32 {
+            for k in (1..(1u64 << n).min(200)).step_by(2) {
+                let number = k * (1 << n) + 1;
+                assert_eq!(is_proth_prime(k, n), is_prime_miller_rabin(number, 0), "{}·2^{} + 1", k, n);
+            }
+        }
+
+        // N above u64::MAX exercises the u128 path: 3·2^63 + 1 = 27670116110564327425 is divisible by 5
+        assert!(!is_proth_prime(3, 63));
+
+        // Exponents of 64 and above are accepted as long as N fits in a u128
+        // 2^64 + 1 = 274177 × 67280421310721, while 25·2^64 + 1 is prime
+        assert!(!is_proth_prime(1, 64));
+        assert!(is_proth_prime(25, 64));
+        assert!(!is_proth_prime(1, 128));
+        assert!(!is_proth_prime(u64::MAX, 127));
+    }
+
+    #[test]
+    fn test_proth_rejects_perfect_squares() {
+        // (2^31 - 1)² = (2^30 - 1)·2^32 + 1 and (2^61 - 1)² = (2^60 - 1)·2^62 + 1
+        // are Proth numbers; without the square check the base search never ends
+        assert!(!is_proth_prime((1 << 30) - 1, 32));
+        assert!(!is_proth_prime((1 << 60) - 1, 62));
+        // 9 = 1·2^3 + 1 and 25 = 3·2^3 + 1
+        assert!(!is_proth_prime(1, 3));
+        assert!(!is_proth_prime(3, 3));
+    }
+
+    #[test]
+    fn test_mersenne_numbers() {
+        use crate::{is_mersenne_number, mersenne_prime_exponents, test_vectors, MAX_MERSENNE_EXPONENT};
+
+        assert_eq!(is_mersenne_number(127), Some(7));
+        assert_eq!(is_mersenne_number(0), Some(0));
+        assert_eq!(is_mersenne_number(1), Some(1));
+        assert_eq!(is_mersenne_number(u64::MAX), Some(64));
+        assert_eq!(is_mersenne_number(128), None);
+        assert_eq!(is_mersenne_number(126), None);
+        assert_eq!(is_mersenne_number(5), None);
+
+        assert_eq!(mersenne_prime_exponents(20), [2, 3, 5, 7, 13, 17, 19]);
+        assert_eq!(
+            mersenne_prime_exponents(MAX_MERSENNE_EXPONENT),
+            [2, 3, 5, 7, 13, 17, 19, 31, 61, 89, 107, 127]
+        );
+        assert!(mersenne_prime_exponents(1).is_empty());
+
+        // Lucas-Lehmer agrees with Miller-Rabin on every Mersenne number in u64
+        for p in 1..=64 {
+            let m = u64::MAX >> (64 - p);
+            assert_eq!(mersenne_prime_exponents(p).last() == Some(&p), is_prime_miller_rabin(m, 0), "M_{}", p);
+        }
+        for &m in test_vectors().mersenne_primes {
+            assert!(is_mersenne_number(m).is_some());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "supports exponents up to 127")]
+    fn test_mersenne_exponents_too_large() {
+        crate::mersenne_prime_exponents(128);
+    }
+
+    #[test]
+    fn test_sophie_germain_and_safe_primes() {
+        use crate::{is_safe_prime, is_sophie_germain_prime};
+
+        let sophie_germain: Vec<u64> = (0..200).filter(|&p| is_sophie_germain_prime(p)).collect();
+        assert_eq!(sophie_germain, [2, 3, 5, 11, 23, 29, 41, 53, 83, 89, 113, 131, 173, 179, 191]);
+
+        let safe: Vec<u64> = (0..200).filter(|&q| is_safe_prime(q)).collect();
+        assert_eq!(safe, [5, 7, 11, 23, 47, 59, 83, 107, 167, 179]);
+
+        // The largest u64 prime has no representable 2p + 1
+        assert!(!is_sophie_germain_prime(18_446_744_073_709_551_557));
+    }
+
+    #[test]
+    fn test_cunningham_chain_first_kind() {
+        use crate::cunningham_chain_first_kind;
+
+        assert_eq!(cunningham_chain_first_kind(2, 5), Some(vec![2, 5, 11, 23, 47]));
+        // 3 → 7 → 15 breaks at once; 5 → 11 → 23 → 47 is the next chain of length 4
+        assert_eq!(cunningham_chain_first_kind(3, 3), Some(vec![5, 11, 23, 47]));
+        // 89 starts the smallest chain of length 6
+        assert_eq!(cunningham_chain_first_kind(3, 6), Some(vec![89, 179, 359, 719, 1439, 2879]));
+        // Chains cannot continue past u64::MAX, so these searches stop at once
+        assert_eq!(cunningham_chain_first_kind(u64::MAX / 2 + 1, 2), None);
+        assert_eq!(cunningham_chain_first_kind(2, 66), None);
+        assert_eq!(cunningham_chain_first_kind(u64::MAX - 58, 1), Some(vec![u64::MAX - 58]));
+    }
+
+    #[test]
+    fn test_best_quadratic_prime_run() {
+        use crate::best_quadratic_prime_run;
+
+        // n² - 5n + 47 is prime for n = 0..=42, beating Euler's n² + n + 41
+        let (a, b, run) = best_quadratic_prime_run(-10..=10, -50..=50);
+        assert_eq!((a, b, run), (-5, 47, 43));
+        for n in 0..run as i64 {
+            assert!(is_prime_miller_rabin((n * n + a * n + b) as u64, 0), "n = {}", n);
+        }
+        // The run ends at n = 43, where the value is 1681 = 41²
+        assert_eq!(run * run - 5 * run + 47, 41 * 41);
+
+        assert_eq!(best_quadratic_prime_run(1..=1, 41..=41), (1, 41, 40));
+        assert_eq!(best_quadratic_prime_run(0..=0, -5..=1), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_is_prime_special_form() {
+        use crate::{classify_special_form, is_prime_special_form, SpecialForm};
+
+        // 2^31 - 1 goes to Lucas-Lehmer, and so do Mersenne numbers beyond u64
+        assert_eq!(classify_special_form(2, 31, -1), SpecialForm::Mersenne);
+        assert!(is_prime_special_form(2, 31, -1));
+        assert!(is_prime_special_form(2, 127, -1));
+        assert!(!is_prime_special_form(2, 67, -1));
+        assert!(!is_prime_special_form(2, 1_000, -1), "composite exponent");
+
+        // Fermat numbers: F_0 to F_4 are prime, F_5 onward are not
+        assert_eq!(classify_special_form(2, 32, 1), SpecialForm::Fermat);
+        for k in 0..5 {
+            assert!(is_prime_special_form(2, 1 << k, 1), "F_{}", k);
+        }
+        for k in 5..32 {
+            assert!(!is_prime_special_form(2, 1 << k, 1), "F_{}", k);
+        }
+
+        // 6^3 + 1 = 27·8 + 1 is not a Proth number (27 > 8); 12^5 + 1 = 243·1024 + 1 is
+        assert_eq!(classify_special_form(6, 3, 1), SpecialForm::General);
+        assert_eq!(classify_special_form(12, 5, 1), SpecialForm::Proth);
+        assert_eq!(is_prime_special_form(12, 5, 1), is_prime_miller_rabin(12u64.pow(5) + 1, 0));
+        assert_eq!(classify_special_form(2, 5, 1), SpecialForm::Proth);
+        assert!(!is_prime_special_form(2, 5, 1), "33 = 3 × 11");
+
+        // Everything else is expanded
+        assert_eq!(classify_special_form(10, 9, 7), SpecialForm::General);
+        assert!(is_prime_special_form(10, 9, 7));
+        assert!(is_prime_special_form(10, 9, 9));
+        assert!(!is_prime_special_form(10, 9, 1));
+        assert!(!is_prime_special_form(3, 2, -20));
+
+        for base in 2..12u64 {
+            for exp in 0..12u32 {
+                for offset in -3..=3i64 {
+                    let value = (base.pow(exp) as i64 + offset).max(0) as u64;
+                    assert_eq!(
+                        is_prime_special_form(base, exp, offset),
+                        is_prime_miller_rabin(value, 0),
+                        "{}^{} + {}", base, exp, offset
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in a u128")]
+    fn test_is_prime_special_form_too_large() {
+        crate::is_prime_special_form(10, 40, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in a u128")]
+    fn test_is_prime_special_form_proth_too_large() {
+        // 288^16 + 1 = 9^16 × 2^80 + 1 has a Proth shape but exceeds u128
+        crate::is_prime_special_form(288, 16, 1);
+    }
+
+    #[test]
+    fn test_twin_prime_near() {
+        assert_eq!(twin_prime_near(100), Some((101, 103)));
+        assert_eq!(twin_prime_near(0), Some((3, 5)));
+        assert_eq!(twin_prime_near(4), Some((3, 5)));
+        assert_eq!(twin_prime_near(5), Some((5, 7)));
+        // 11 and 17 are both at distance 3 from 14; the lower pair wins
+        assert_eq!(twin_prime_near(14), Some((11, 13)));
+
+        for target in [1_000u64, 123_456_789, 1 << 40, u64::MAX - 1] {
+            let (p, q) = twin_prime_near(target).unwrap();
+            assert_eq!(q, p + 2);
+            assert!(is_prime_miller_rabin(p, 0) && is_prime_miller_rabin(q, 0));
+            // No twin pair lies strictly closer to the target
+            if let Some(closer) = p.abs_diff(target).checked_sub(1) {
+                for r in target.saturating_sub(closer)..=target.saturating_add(closer).min(u64::MAX - 2) {
+                    assert!(!(is_prime_miller_rabin(r, 0) && is_prime_miller_rabin(r + 2, 0)), "{} is closer to {}", r, target);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod zeta_tests {
+    use crate::{
+        benchmark_accuracy_tradeoff, is_prime_miller_rabin, is_prime_zeta, optimal_zeros_for, oscillation_profile,
+        primes_in_range, psi_analysis, von_mangoldt, PROFILE_RADIUS,
+    };
+
+    #[test]
+    fn test_zeta_never_skips_large_factors() {
+        // Semiprimes whose smallest factor lies above 1000, where the trial
+        // division used to be allowed to skip candidate divisors
+        let primes = primes_in_range(1001..=1400);
+        for pair in primes.windows(2) {
+            let n = pair[0] * pair[1];
+            assert!(!is_prime_zeta(n), "{} = {} × {} reported prime", n, pair[0], pair[1]);
+        }
+        assert!(!is_prime_zeta(1_000_003u64 * 1_000_033));
+    }
+
+    #[test]
+    fn test_zeta_matches_miller_rabin_window() {
+        for n in 1_000_000u64..1_020_000 {
+            assert_eq!(is_prime_zeta(n), is_prime_miller_rabin(n, 0), "mismatch at {}", n);
+        }
+    }
+
+    #[test]
+    fn test_oscillation_profile() {
+        let profile = oscillation_profile(1_000, 20);
+        assert_eq!(profile.len(), 2 * PROFILE_RADIUS as usize + 1);
+        assert!(profile.iter().all(|v| v.is_finite()));
+
+        // The window is clamped at 1 near the origin
+        assert_eq!(oscillation_profile(3, 20).len(), 3 + PROFILE_RADIUS as usize);
+        assert!(oscillation_profile(0, 50).iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_zeta_try_is_prime_out_of_range() {
+        use crate::{PrimalityError, PrimalityTest, SieveAlgorithm, ZetaAlgorithm, ZETA_MAX_RELIABLE};
+
+        let zeta = ZetaAlgorithm;
+        assert_eq!(PrimalityTest::<u64>::max_reliable(&zeta), ZETA_MAX_RELIABLE);
+        assert_eq!(zeta.try_is_prime(1_000_000_007u64), Ok(true));
+        assert_eq!(zeta.try_is_prime(ZETA_MAX_RELIABLE), Ok(false));
+
+        let out_of_range = Err(PrimalityError::OutOfRange { max_reliable: ZETA_MAX_RELIABLE });
+        assert_eq!(zeta.try_is_prime(1_000_000_000_000_000_003u64), out_of_range);
+        assert_eq!(zeta.try_is_prime(18_446_744_073_709_551_557u64), out_of_range);
+        assert_eq!(zeta.try_is_prime(u128::MAX), out_of_range);
+
+        // Algorithms without a limit accept everything
+        assert_eq!(PrimalityTest::<u64>::max_reliable(&SieveAlgorithm::default()), u64::MAX);
+        assert_eq!(SieveAlgorithm::default().try_is_prime(1_000_000_007u64), Ok(true));
+    }
+
+    #[test]
+    fn test_benchmark_accuracy_tradeoff() {
+        let report = benchmark_accuracy_tradeoff();
+        let zero_counts: Vec<usize> = report.iter().map(|&(num_zeros, _, _)| num_zeros).collect();
+        assert_eq!(zero_counts, [5, 10, 15, 20, 25, 30, 35, 40, 45, 50]);
+        for &(num_zeros, accuracy, _) in &report {
+            assert!((0.0..=1.0).contains(&accuracy), "accuracy {} with {} zeros", accuracy, num_zeros);
+        }
+    }
+
+    #[test]
+    fn test_psi_analysis() {
+        for p in [2u64, 3, 5, 7, 11, 97, 1009, 7919] {
+            let analysis = psi_analysis(p, 50);
+            assert_eq!(analysis.n, p);
+            assert_eq!(analysis.actual, (p as f64).ln(), "Λ({}) should be ln {}", p, p);
+            assert!(analysis.estimate.is_finite());
+            assert_eq!(analysis.error, analysis.estimate - analysis.actual);
+        }
+
+        assert_eq!(von_mangoldt(8), 2f64.ln());
+        assert_eq!(von_mangoldt(81), 3f64.ln());
+        assert_eq!(von_mangoldt(6), 0.0);
+        assert_eq!(von_mangoldt(1), 0.0);
+        assert_eq!(psi_analysis(6, 50).actual, 0.0);
+    }
+
+    #[test]
+    fn test_zeta_small_numbers_use_lookup() {
+        // Below 100 the answer comes from trivial_primality or one table
+        // lookup, for every integer width
+        for n in 0u8..100 {
+            let expected = is_prime_miller_rabin(n as u64, 0);
+            assert_eq!(is_prime_zeta(n), expected, "u8 {}", n);
+            assert_eq!(is_prime_zeta(n as u16), expected, "u16 {}", n);
+            assert_eq!(is_prime_zeta(n as u32), expected, "u32 {}", n);
+            assert_eq!(is_prime_zeta(n as u64), expected, "u64 {}", n);
+        }
+    }
+
+    #[test]
+    fn test_optimal_zeros_for_is_monotone() {
+        use crate::{PrimalityTest, ZetaAlgorithm};
+
+        let samples = (0..64).flat_map(|bit| [1u64 << bit, (1u64 << bit) + 1, (1u64 << bit) | (1u64 << bit >> 1)]);
+        let mut samples: Vec<u64> = samples.chain([0, 4_999, 5_000, u64::MAX]).collect();
+        samples.sort_unstable();
+
+        let counts: Vec<usize> = samples.iter().map(|&n| optimal_zeros_for(n)).collect();
+        assert!(counts.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", counts);
+        assert!(counts.iter().all(|&zeros| (1..=50).contains(&zeros)));
+        assert!(optimal_zeros_for(4_999) < optimal_zeros_for(5_000));
+
+        // The cost model charges for the zeros the test actually sums, plus √n / 2 divisions
+        assert_eq!(PrimalityTest::<u64>::estimate_cost(&ZetaAlgorithm, 3), 8 * optimal_zeros_for(3) as u64);
+        assert_eq!(PrimalityTest::<u64>::estimate_cost(&ZetaAlgorithm, 1_000_000), 8 * 4 + 500);
+    }
+}
+
+#[cfg(test)]
+mod factor_tests {
+    use crate::{
+        classify_number, classify_prime_power, count_divisors, distinct_prime_factors, divisors, factor_tree, factorize, has_small_factor, is_prime_miller_rabin,
+        prime_signature, radical, sum_of_two_squares, test_vectors, FactorNode, PrimePowerClass,
+    };
+
+    #[test]
+    fn test_factorize_small() {
+        assert!(factorize(0).is_empty());
+        assert!(factorize(1).is_empty());
+        assert_eq!(factorize(2), [2]);
+        assert_eq!(factorize(360), [2, 2, 2, 3, 3, 5]);
+        assert_eq!(factorize(1_000_000_007), [1_000_000_007]);
+        assert_eq!(factorize(1 << 63), [2; 63]);
+        assert_eq!(factorize(u64::MAX), [3, 5, 17, 257, 641, 65537, 6_700_417]);
+    }
+
+    #[test]
+    fn test_factorize_hard_semiprimes() {
+        assert_eq!(factorize(9_999_997_000_029_991), [17, 131, 1303, 15761, 218_651]);
+        assert_eq!(factorize(10_000_020_999_973), [1_000_003, 9_999_991]);
+        assert_eq!(factorize(10_000_004_400_000_259), [100_000_007, 100_000_037]);
+        assert_eq!(factorize(1_000_000_016_000_000_063), [1_000_000_007, 1_000_000_009]);
+        assert_eq!(factorize(4_294_967_291 * 4_294_967_291), [4_294_967_291, 4_294_967_291]);
+
+        for &n in test_vectors().very_large_composites {
+            let factors = factorize(n);
+            assert_eq!(factors.len(), 2, "{} should be a semiprime", n);
+            assert!(factors.iter().all(|&p| is_prime_miller_rabin(p, 0)));
+            assert_eq!(factors.iter().product::<u64>(), n);
+        }
+    }
+
+    #[test]
+    fn test_has_small_factor() {
+        // 1009 × 100000007: found once the bound reaches the smaller factor
+        let n = 1009 * 100_000_007;
+        assert_eq!(has_small_factor(n, 1000), None);
+        assert_eq!(has_small_factor(n, 1009), Some(1009));
+        assert_eq!(has_small_factor(n, 70_000), Some(1009));
+        assert_eq!(has_small_factor(100_003 * 100_019, 100_002), None);
+        assert_eq!(has_small_factor(100_003 * 100_019, 100_003), Some(100_003));
+
+        assert_eq!(has_small_factor(360, 100), Some(2));
+        assert_eq!(has_small_factor(97, 100), None);
+        assert_eq!(has_small_factor(0, 100), None);
+        assert_eq!(has_small_factor(1, 100), None);
+
+        // 9999991 × 1000003 from the benchmark composites
+        let semiprime = test_vectors().very_large_composites[0];
+        assert_eq!(has_small_factor(semiprime, 1_000_002), None);
+        assert_eq!(has_small_factor(semiprime, 1_000_003), Some(1_000_003));
+    }
+
+    #[test]
+    fn test_number_class() {
+        use crate::{count_divisors, number_class, sum_divisors, NumberClass};
+
+        for n in [6u64, 28, 496, 8128, 33_550_336, 8_589_869_056] {
+            assert_eq!(number_class(n), NumberClass::Perfect, "{} is perfect", n);
+        }
+        for n in [12u64, 18, 20, 24, 945, 5040] {
+            assert_eq!(number_class(n), NumberClass::Abundant, "{} is abundant", n);
+        }
+        for n in [1u64, 2, 8, 9, 1 << 40, 3u64.pow(30), 1_000_000_007] {
+            assert_eq!(number_class(n), NumberClass::Deficient, "{} is deficient", n);
+        }
+
+        assert_eq!(count_divisors(1), 1);
+        assert_eq!(count_divisors(360), 24);
+        assert_eq!(count_divisors(1_000_000_007), 2);
+        assert_eq!(count_divisors(0), 0);
+        assert_eq!(sum_divisors(12), 28);
+        assert_eq!(sum_divisors(1 << 63), (1u128 << 64) - 1);
+
+        // σ(n) overflows u64 here, which is why it is returned as a u128
+        let highly_abundant = 18_401_055_938_125_660_800u64;
+        assert!(sum_divisors(highly_abundant) > u64::MAX as u128);
+        assert_eq!(number_class(highly_abundant), NumberClass::Abundant);
+    }
+
+    #[test]
+    fn test_factorize_flat_and_powers() {
+        use crate::{factorize_flat, factorize_powers};
+
+        assert_eq!(factorize_flat(360), [2, 2, 2, 3, 3, 5]);
+        assert_eq!(factorize_powers(360), [(2, 3), (3, 2), (5, 1)]);
+        assert!(factorize_flat(1).is_empty());
+        assert!(factorize_powers(0).is_empty());
+
+        for n in (2..5_000u64).chain([u64::MAX, 600_851_475_143, 1 << 63]) {
+            let expanded: Vec<u64> = factorize_powers(n)
+                .into_iter()
+                .flat_map(|(p, e)| std::iter::repeat_n(p, e as usize))
+                .collect();
+            assert_eq!(factorize_flat(n), expanded, "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_sum_of_two_squares() {
+        assert_eq!(sum_of_two_squares(13), Some((2, 3)));
+        assert_eq!(sum_of_two_squares(5), Some((1, 2)));
+        assert_eq!(sum_of_two_squares(3), None);
+        // 50 = 1² + 7² = 5² + 5², either decomposition is acceptable
+        let (a, b) = sum_of_two_squares(50).unwrap();
+        assert_eq!(a * a + b * b, 50);
+        assert!(a <= b);
+
+        for n in 0..2000u64 {
+            let expected = (0..=n.isqrt()).any(|a| (n - a * a).isqrt().pow(2) == n - a * a);
+            match sum_of_two_squares(n) {
+                Some((a, b)) => assert_eq!(a * a + b * b, n, "decomposition of {}", n),
+                None => assert!(!expected, "{} is a sum of two squares", n),
+            }
+            assert_eq!(sum_of_two_squares(n).is_some(), expected, "{}", n);
+        }
+        let (a, b) = sum_of_two_squares(18_446_744_073_709_551_557).unwrap();
+        assert_eq!(a as u128 * a as u128 + b as u128 * b as u128, 18_446_744_073_709_551_557);
+    }
+
+    #[test]
+    fn test_radical() {
+        assert_eq!(radical(12), 6);
+        assert_eq!(radical(97), 97);
+        assert_eq!(radical(1), 1);
+        assert_eq!(radical(360), 30);
+        assert_eq!(radical(0), 0);
+        assert_eq!(radical(1 << 63), 2);
+        assert_eq!(radical(18_446_744_073_709_551_557), 18_446_744_073_709_551_557);
+
+        assert_eq!(distinct_prime_factors(360), [2, 3, 5]);
+        assert!(distinct_prime_factors(1).is_empty());
+        for n in 1..2000u64 {
+            let rad = radical(n);
+            assert!(n.is_multiple_of(rad));
+            assert_eq!(rad, distinct_prime_factors(n).iter().product::<u64>());
+        }
+    }
+
+    #[test]
+    fn test_factor_tree() {
+        let tree = factor_tree(360);
+        let leaves = tree.leaves();
+        assert_eq!(leaves.iter().product::<u64>(), 360);
+        assert!(leaves.iter().all(|&p| is_prime_miller_rabin(p, 0)));
+        let mut sorted = leaves.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, factorize(360));
+
+        assert_eq!(factor_tree(1), FactorNode::Leaf(1));
+        assert_eq!(factor_tree(97), FactorNode::Leaf(97));
+        assert_eq!(factor_tree(12).to_string(), "12\n  2\n  6\n    2\n    3\n");
+
+        // Large prime factors come from Pollard's rho
+        let n = 4_294_967_291u64 * 4_294_967_279;
+        let mut leaves = factor_tree(n).leaves();
+        leaves.sort_unstable();
+        assert_eq!(leaves, [4_294_967_279, 4_294_967_291]);
+    }
+
+    #[test]
+    fn test_prime_signature() {
+        for &p in test_vectors().large_primes {
+            assert_eq!(prime_signature(p), [1]);
+            if let Some(square) = p.checked_mul(p) {
+                assert_eq!(prime_signature(square), [2]);
+            }
+        }
+        assert_eq!(prime_signature(360), [3, 2, 1]);
+        assert_eq!(prime_signature(2250), [3, 2, 1]);
+        assert_eq!(prime_signature(1 << 40), [40]);
+        assert!(prime_signature(1).is_empty());
+        assert!(prime_signature(0).is_empty());
+
+        // The divisor count depends on the signature alone
+        for n in 1..2000u64 {
+            let from_signature: u64 = prime_signature(n).iter().map(|&e| e as u64 + 1).product();
+            assert_eq!(from_signature, count_divisors(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(12), [1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(1_000_003), [1, 1_000_003]);
+        assert_eq!(divisors(1), [1]);
+        assert!(divisors(0).is_empty());
+        assert_eq!(divisors(1 << 10), (0..=10).map(|k| 1u64 << k).collect::<Vec<_>>());
+
+        for n in 1..=2_000u64 {
+            let brute: Vec<u64> = (1..=n).filter(|d| n.is_multiple_of(*d)).collect();
+            assert_eq!(divisors(n), brute, "n = {}", n);
+            assert_eq!(divisors(n).len() as u64, count_divisors(n));
+        }
+    }
+
+    #[test]
+    fn test_classify_prime_power() {
+        use crate::factorize_powers;
+
+        assert_eq!(classify_prime_power(7), PrimePowerClass::Prime);
+        assert_eq!(classify_prime_power(8), PrimePowerClass::PrimePower { base: 2, exp: 3 });
+        assert_eq!(classify_prime_power(12), PrimePowerClass::Composite);
+        assert_eq!(classify_prime_power(49), PrimePowerClass::PrimePower { base: 7, exp: 2 });
+        assert_eq!(classify_prime_power(36), PrimePowerClass::Composite);
+        assert_eq!(classify_prime_power(0), PrimePowerClass::Composite);
+        assert_eq!(classify_prime_power(1), PrimePowerClass::Composite);
+        assert_eq!(
+            classify_prime_power(1_000_003 * 1_000_003),
+            PrimePowerClass::PrimePower { base: 1_000_003, exp: 2 }
+        );
+
+        for n in 2..=5_000u64 {
+            let expected = match factorize_powers(n).as_slice() {
+                [(_, 1)] => PrimePowerClass::Prime,
+                &[(base, exp)] => PrimePowerClass::PrimePower { base, exp },
+                _ => PrimePowerClass::Composite,
+            };
+            assert_eq!(classify_prime_power(n), expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_classify_number() {
+        use crate::{is_carmichael, NumberClass};
+
+        let summary = classify_number(28);
+        assert!(!summary.is_prime);
+        assert_eq!(summary.factors, [(2, 2), (7, 1)]);
+        assert_eq!(summary.class, NumberClass::Perfect);
+        assert_eq!(summary.divisor_count, 6);
+        assert!(!summary.is_carmichael);
+        assert_eq!(summary.perfect_power, None);
+
+        let prime = classify_number(1_000_003);
+        assert!(prime.is_prime && prime.divisor_count == 2 && prime.class == NumberClass::Deficient);
+        assert_eq!(classify_number(1 << 20).perfect_power, Some((2, 20)));
+        let zero = classify_number(0);
+        assert!(!zero.is_prime && zero.factors.is_empty() && zero.divisor_count == 0);
+
+        for &n in test_vectors().carmichael {
+            assert!(classify_number(n).is_carmichael, "{} is a Carmichael number", n);
+        }
+        let carmichael: Vec<u64> = (0..30_000).filter(|&n| is_carmichael(n)).collect();
+        assert_eq!(carmichael, test_vectors().carmichael);
+
+        for n in 0..2_000u64 {
+            let summary = classify_number(n);
+            assert_eq!(summary.is_prime, crate::is_prime_miller_rabin(n, 0), "n = {}", n);
+            assert_eq!(summary.divisor_count, count_divisors(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_factorize_struct() {
+        use crate::{factorize_struct, Factorization};
+
+        let factorization = factorize_struct(360);
+        assert_eq!(factorization, Factorization { n: 360, factors: vec![(2, 3), (3, 2), (5, 1)] });
+        for n in (1..5_000u64).chain([u64::MAX, 1_000_003 * 999_983, 1 << 63]) {
+            let factorization = factorize_struct(n);
+            assert_eq!(factorization.product(), Some(n), "n = {}", n);
+        }
+        assert_eq!(factorize_struct(0).product(), Some(1));
+
+        let overflowing = Factorization { n: 0, factors: vec![(2, 64)] };
+        assert_eq!(overflowing.product(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_factorization_serde_round_trip() {
+        use crate::{factorize_struct, Factorization};
+
+        for n in [0u64, 1, 360, 1_000_003 * 999_983, u64::MAX] {
+            let factorization = factorize_struct(n);
+            let json = serde_json::to_string(&factorization).unwrap();
+            let restored: Factorization = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, factorization, "{}", json);
+            assert_eq!(restored.product(), Some(n.max(1)));
+        }
+        assert_eq!(serde_json::to_string(&factorize_struct(12)).unwrap(), r#"{"n":12,"factors":[[2,2],[3,1]]}"#);
+    }
+
+    #[test]
+    fn test_pollard_rho_bounded() {
+        use crate::{pollard_rho_bounded, FactorError};
+
+        // Two 32-bit prime factors: rho needs tens of thousands of iterations
+        let (p, q) = (4_294_967_279u64, 4_294_967_291u64);
+        let hard = p * q;
+        assert_eq!(pollard_rho_bounded(hard, 100), Err(FactorError::Budget { iterations: 100 }));
+        assert_eq!(pollard_rho_bounded(hard, 1), Err(FactorError::Budget { iterations: 1 }));
+        let divisor = pollard_rho_bounded(hard, 1 << 22).unwrap().unwrap();
+        assert!(divisor == p || divisor == q, "{}", divisor);
+
+        assert_eq!(pollard_rho_bounded(1_000_003, 1), Ok(None));
+        assert_eq!(pollard_rho_bounded(0, 1), Ok(None));
+        assert_eq!(pollard_rho_bounded(1, 1), Ok(None));
+        assert_eq!(pollard_rho_bounded(1 << 40, 0), Ok(Some(2)));
+        assert!(pollard_rho_bounded(91, 0).is_err());
+
+        for n in (4..5_000u64).filter(|&n| !is_prime_miller_rabin(n, 0)) {
+            let divisor = pollard_rho_bounded(n, 10_000).unwrap().unwrap();
+            assert!(divisor > 1 && divisor < n && n.is_multiple_of(divisor), "n = {}: {}", n, divisor);
+        }
+    }
+
+    #[test]
+    fn test_factorize_js() {
+        use crate::{factorize, factorize_js};
+
+        assert_eq!(factorize_js(360), [2, 2, 2, 3, 3, 5]);
+        assert!(factorize_js(0).is_empty() && factorize_js(1).is_empty());
+        for n in (0..3_000u64).chain([1_000_003 * 999_983, u64::MAX, 1 << 63]) {
+            let factors = factorize_js(n);
+            assert_eq!(factors, factorize(n), "n = {}", n);
+            if n > 0 {
+                assert_eq!(factors.iter().product::<u64>(), n);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod bpsw_tests {
+    use crate::{
+        is_lucas_prp_with_params, is_prime_miller_rabin, is_strong_lucas_probable_prime, jacobi, probable_prime_report,
+        RandomSource, SplitMix64,
+    };
+
+    #[test]
+    fn test_report_on_base_2_pseudoprime() {
+        // 2047 = 23 × 89 is the smallest strong pseudoprime to base 2
+        let report = probable_prime_report(2047);
+        assert!(report.strong_base_2);
+        assert!(!report.strong_lucas);
+        assert_eq!(report.small_factor, Some(23));
+        assert!(!report.verdict);
+
+        // 25326001 = 2251 × 11251 has no factor below the trial division bound
+        let report = probable_prime_report(25_326_001);
+        assert!(report.strong_base_2);
+        assert!(!report.strong_lucas);
+        assert_eq!(report.small_factor, None);
+        assert!(!report.verdict);
+    }
+
+    #[test]
+    fn test_strong_lucas_pseudoprimes() {
+        // The smallest strong Lucas pseudoprimes are rejected by base 2
+        for n in [5459u64, 5777, 10877, 16109, 18971] {
+            assert!(is_strong_lucas_probable_prime(n), "{} is a strong Lucas pseudoprime", n);
+            assert!(!probable_prime_report(n).strong_base_2);
+            assert!(!probable_prime_report(n).verdict);
+        }
+    }
+
+    #[test]
+    fn test_bpsw_matches_miller_rabin() {
+        for n in 0u64..20_000 {
+            assert_eq!(probable_prime_report(n).verdict, is_prime_miller_rabin(n, 0), "mismatch at {}", n);
+        }
+
+        let mut rng = SplitMix64::new(936);
+        for _ in 0..20_000 {
+            let n = rng.next_u64() | 1;
+            assert_eq!(probable_prime_report(n).verdict, is_prime_miller_rabin(n, 0), "mismatch at {}", n);
+        }
+
+        let report = probable_prime_report(18_446_744_073_709_551_557);
+        assert!(report.strong_base_2 && report.strong_lucas && report.verdict);
+        assert!(!is_strong_lucas_probable_prime(1_000_003 * 1_000_003));
+    }
+
+    /// Selfridge's method A parameters (P, Q) = (1, (1 - D) / 4) for odd non-square n,
+    /// or `None` when the search meets a D sharing a proper factor with n first
+    fn selfridge_parameters(n: u64) -> Option<(i64, i64)> {
+        let mut d: i64 = 5;
+        loop {
+            let d_mod = if d > 0 { d as u64 % n } else { (n - d.unsigned_abs() % n) % n };
+            match jacobi(d_mod, n) {
+                -1 => return Some((1, (1 - d) / 4)),
+                0 if d.unsigned_abs() != n => return None,
+                _ => d = if d > 0 { -(d + 2) } else { -d + 2 },
+            }
+        }
+    }
+
+    #[test]
+    fn test_lucas_prp_with_params() {
+        // With Selfridge's parameters both functions run the same test
+        for n in (3..20_000u64).step_by(2) {
+            if n.isqrt().pow(2) == n {
+                continue;
+            }
+            let Some((p, q)) = selfridge_parameters(n) else {
+                continue;
+            };
+            assert_eq!(is_lucas_prp_with_params(n, p, q), is_strong_lucas_probable_prime(n), "n = {}", n);
+        }
+        // 5459 and 5777 are the first strong Lucas pseudoprimes for Selfridge's choice
+        assert_eq!(selfridge_parameters(5459), Some((1, 2)));
+        assert!(!is_prime_miller_rabin(5459u64, 0) && is_lucas_prp_with_params(5459, 1, 2));
+        assert_eq!(selfridge_parameters(5777), Some((1, -1)));
+        assert!(!is_prime_miller_rabin(5777u64, 0) && is_lucas_prp_with_params(5777, 1, -1));
+
+        // A fixed (P, Q) = (3, 1), D = 5, accepts every prime coprime to 5
+        let mut passing_composites = 0;
+        for n in (7..20_000u64).step_by(2) {
+            let verdict = is_lucas_prp_with_params(n, 3, 1);
+            if is_prime_miller_rabin(n, 0) {
+                assert!(verdict, "prime {} rejected", n);
+            } else if verdict {
+                passing_composites += 1;
+            }
+        }
+        // A fixed D lets a few composites through, whatever their Jacobi symbol
+        assert_eq!(passing_composites, 12);
+
+        // Unusable parameters: D = 0, or Q sharing a factor with n
+        assert!(!is_lucas_prp_with_params(101, 2, 1));
+        assert!(!is_lucas_prp_with_params(101, 1, 101));
+        assert!(is_lucas_prp_with_params(2, 1, -1));
+        assert!(!is_lucas_prp_with_params(u64::MAX, 1, -1));
+    }
+}
+
+#[cfg(test)]
+mod generate_tests {
+    use crate::{bit_length, is_prime_miller_rabin, GenError, PrimeGenerator, SplitMix64};
+
+    #[test]
+    fn test_generate_safe_prime() {
+        let mut rng = SplitMix64::new(20);
+        let p = PrimeGenerator::new().bits(20).safe().generate(&mut rng).unwrap();
+        assert_eq!(bit_length(p), 20);
+        assert!(is_prime_miller_rabin(p, 0));
+        assert!(is_prime_miller_rabin((p - 1) / 2, 0));
+
+        // The same seed replays the same prime
+        let again = PrimeGenerator::new().bits(20).safe().generate(&mut SplitMix64::new(20));
+        assert_eq!(again, Ok(p));
+    }
+
+    #[test]
+    fn test_generate_constraints() {
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..50 {
+            let p = PrimeGenerator::new().bits(32).congruent_to(3, 4).generate(&mut rng).unwrap();
+            assert_eq!(p % 4, 3);
+            assert!(is_prime_miller_rabin(p, 0) && bit_length(p) == 32);
+
+            let q = PrimeGenerator::new().bits(48).sophie_germain().generate(&mut rng).unwrap();
+            assert!(is_prime_miller_rabin(q, 0) && is_prime_miller_rabin(2 * q + 1, 0));
+        }
+        assert_eq!(PrimeGenerator::new().bits(2).generate(&mut rng).map(|p| p == 2 || p == 3), Ok(true));
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let mut rng = SplitMix64::new(1);
+        assert_eq!(PrimeGenerator::new().bits(65).generate(&mut rng), Err(GenError::InvalidBits(65)));
+        assert_eq!(PrimeGenerator::new().bits(1).generate(&mut rng), Err(GenError::InvalidBits(1)));
+        assert_eq!(PrimeGenerator::new().congruent_to(1, 0).generate(&mut rng), Err(GenError::ZeroModulus));
+        // 4-bit values are 8..=15, none of which is 100 more than a multiple of 1000
+        assert_eq!(PrimeGenerator::new().bits(4).congruent_to(100, 1000).generate(&mut rng), Err(GenError::NoCandidates));
+        // Even numbers above 2 are never prime
+        assert_eq!(
+            PrimeGenerator::new().bits(16).congruent_to(0, 2).max_attempts(100).generate(&mut rng),
+            Err(GenError::Exhausted { attempts: 100 })
+        );
+    }
+}
+
+#[cfg(test)]
+mod suite_tests {
+    use crate::{benchmark_buckets, run_benchmark_suite, score_algorithms, test_vectors, FnAlgorithm, PrimalityRegistry};
+
+    #[test]
+    fn test_benchmark_suite_covers_every_bucket() {
+        let registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        let result = run_benchmark_suite(&registry);
+        let buckets = benchmark_buckets();
+
+        assert_eq!(result.timings.len(), registry.len() * buckets.len());
+        for algo in registry.algorithms() {
+            for &(bucket, numbers) in &buckets {
+                let timing = result.get(algo.name(), bucket).expect("missing timing");
+                assert_eq!(timing.inputs, numbers.len());
+                let expected_primes = if bucket.ends_with("_primes") { numbers.len() } else { 0 };
+                assert_eq!(timing.primes, expected_primes, "{} on {}", algo.name(), bucket);
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_benchmark_suite_serializes_to_json() {
+        let mut registry = PrimalityRegistry::<u64>::empty();
+        registry.register(crate::FnAlgorithm::new("Even", |n| n == 2));
+        let json = serde_json::to_value(run_benchmark_suite(&registry)).unwrap();
+
+        let timings = json["timings"].as_array().unwrap();
+        assert_eq!(timings.len(), benchmark_buckets().len());
+        assert_eq!(timings[0]["algorithm"], "Even");
+        assert_eq!(timings[0]["bucket"], "small_primes");
+        assert!(timings[0]["elapsed"]["nanos"].is_u64());
+    }
+
+    #[test]
+    fn test_score_algorithms_ranks_miller_rabin_first() {
+        let mut registry = PrimalityRegistry::<u64>::with_all_algorithms();
+        registry.register(FnAlgorithm::new("Always prime", |_| true));
+        let vectors = test_vectors();
+        let workload: Vec<u64> = vectors.very_large_primes.iter().chain(vectors.very_large_composites).copied().collect();
+
+        let scores = score_algorithms(&registry, &workload);
+        assert_eq!(scores.len(), 4);
+        assert_eq!(scores[0].name, "Miller-Rabin", "{:?}", scores);
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(scores.iter().all(|entry| (0.0..=1.0).contains(&entry.score)));
+
+        // Speed cannot make up for answering wrong half the time
+        let always = scores.iter().find(|entry| entry.name == "Always prime").unwrap();
+        assert!(always.accuracy_vs_oracle < 0.9);
+        assert_eq!(scores.last().unwrap().name, "Always prime");
+        for entry in scores.iter().filter(|entry| entry.name != "Always prime") {
+            assert_eq!(entry.accuracy_vs_oracle, 1.0, "{}", entry.name);
+        }
+    }
 }
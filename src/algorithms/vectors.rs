@@ -0,0 +1,110 @@
+/// Curated corpus of numbers with known primality
+///
+/// Shared by the test suite and the benchmarks so that both exercise the same
+/// inputs, and exposed publicly so that external implementations can be
+/// validated against it. Every list is sorted in ascending order and every
+/// entry fits in a u64.
+#[derive(Clone, Copy, Debug)]
+pub struct TestVectors {
+    /// Primes below 200
+    pub small_primes: &'static [u64],
+    /// Composites below 200
+    pub small_composites: &'static [u64],
+    /// Primes between 10^4 and 10^6
+    pub medium_primes: &'static [u64],
+    /// Composites between 10^3 and 10^8, mostly near powers of ten
+    pub medium_composites: &'static [u64],
+    /// Primes between 10^3 and 10^10
+    pub large_primes: &'static [u64],
+    /// Powers of ten between 10^3 and 10^9
+    pub large_composites: &'static [u64],
+    /// Primes between 10^10 and 10^13
+    pub very_large_primes: &'static [u64],
+    /// Semiprimes with two large factors, the hardest case for trial division
+    pub very_large_composites: &'static [u64],
+    /// Carmichael numbers: composites that pass Fermat's test to every coprime base
+    pub carmichael: &'static [u64],
+    /// Mersenne primes 2^p - 1 that fit in 32 bits
+    pub mersenne_primes: &'static [u64],
+    /// Fermat primes 2^(2^k) + 1, all five known ones
+    pub fermat_primes: &'static [u64],
+}
+
+impl TestVectors {
+    /// Returns every prime of the corpus, grouped by list
+    pub fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        [
+            self.small_primes,
+            self.medium_primes,
+            self.large_primes,
+            self.very_large_primes,
+            self.mersenne_primes,
+            self.fermat_primes,
+        ]
+        .into_iter()
+        .flatten()
+        .copied()
+    }
+
+    /// Returns every composite of the corpus, grouped by list
+    pub fn composites(&self) -> impl Iterator<Item = u64> + '_ {
+        [
+            self.small_composites,
+            self.medium_composites,
+            self.large_composites,
+            self.very_large_composites,
+            self.carmichael,
+        ]
+        .into_iter()
+        .flatten()
+        .copied()
+    }
+}
+
+/// Returns the curated prime and composite test vectors
+///
+/// # Returns
+///
+/// The shared corpus used by the crate's tests and benchmarks
+pub fn test_vectors() -> TestVectors {
+    TestVectors {
+        small_primes: &[
+            5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
+            83, 89, 97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181,
+            191, 193, 197,
+        ],
+        small_composites: &[
+            4, 6, 8, 9, 10, 12, 14, 15, 16, 18, 20, 21, 22, 24, 25, 26, 27, 28, 30, 32,
+            100, 102, 104, 105, 106, 108, 110, 111, 112, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124,
+        ],
+        medium_primes: &[
+            10007, 10009, 10037, 10039, 10061, 10067, 10069, 10079, 10091, 10093,
+            100003, 100019, 100043, 100049, 100057, 100069, 100103, 100109, 100129, 100151,
+        ],
+        medium_composites: &[
+            1000, 1001, 1002, 10000, 10001, 10002, 100000, 100001, 100002,
+            1000000, 1000001, 1000002, 10000000, 10000001, 10000002,
+        ],
+        large_primes: &[
+            1009, 10007, 100003,
+            1_000_003, 1_000_033, 1_000_037, 1_000_039, 1_000_081,
+            10_000_019, 10_000_079, 10_000_139, 10_000_169, 10_000_189,
+            100_000_007, 100_000_037, 100_000_039, 100_000_049, 100_000_073,
+            1_000_000_007, 1_000_000_009, 1_000_000_021, 1_000_000_033, 1_000_000_087,
+        ],
+        large_composites: &[1000, 10000, 100000, 1000000, 10000000, 100000000, 1000000000],
+        very_large_primes: &[
+            10_000_000_019, 10_000_000_033, 10_000_000_061, 10_000_000_069, 10_000_000_097,
+            100_000_000_003, 100_000_000_019, 100_000_000_057, 100_000_000_063, 100_000_000_069,
+            1_000_000_000_039,
+        ],
+        very_large_composites: &[
+            10_000_020_999_973,     // 9999991 * 1000003
+            10_000_389_000_703,     // 10000019 * 1000037
+            10_000_004_400_000_259, // 100000007 * 100000037
+        ],
+        carmichael: &[561, 1105, 1729, 2465, 2821, 6601, 8911, 10585, 15841, 29341],
+        mersenne_primes: &[3, 7, 31, 127, 8191, 131071, 524287, 2147483647],
+        fermat_primes: &[3, 5, 17, 257, 65537],
+    }
+}
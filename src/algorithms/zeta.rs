@@ -1,4 +1,10 @@
-use super::PrimalityTest;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use super::arith::{saturating_from_u64, trial_division_limit};
+use super::factor::von_mangoldt;
+use super::miller_rabin::is_prime_miller_rabin;
+use super::{trivial_primality, AlgorithmInfo, Assessment, Certainty, Complexity, PrimalityTest};
 use num_traits::{PrimInt, ToPrimitive, FromPrimitive, Unsigned};
 
 /// Primality test based on the Riemann zeta function oscillatory signature
@@ -42,7 +48,7 @@ use num_traits::{PrimInt, ToPrimitive, FromPrimitive, Unsigned};
 ///
 /// Assumes RH (all zeros on critical line Re(s) = 1/2).
 /// Using more zeros improves accuracy but increases computation time.
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct ZetaAlgorithm;
 
 impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityTest<N> for ZetaAlgorithm {
@@ -53,8 +59,43 @@ impl<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned> PrimalityTest<N> for Z
     fn is_prime(&self, n: N) -> bool {
         is_prime_zeta(n)
     }
+
+    fn estimate_cost(&self, n: N) -> u64 {
+        // Spectral score over the zeros `is_prime` sums (a few evaluations per
+        // zero), followed by trial division up to √n in every branch
+        let spectral = 8 * optimal_zeros_for(n.to_u64().unwrap_or(u64::MAX)) as u64;
+        let trial = (n.to_f64().unwrap_or(f64::MAX).sqrt() / 2.0) as u64;
+        spectral.saturating_add(trial)
+    }
+
+    fn describe(&self) -> AlgorithmInfo {
+        AlgorithmInfo {
+            name: PrimalityTest::<N>::name(self),
+            complexity: Complexity::Heuristic,
+        }
+    }
+
+    fn max_reliable(&self) -> u64 {
+        ZETA_MAX_RELIABLE
+    }
+
+    fn assess(&self, n: N) -> Assessment {
+        // The spectral score only decides the order of the divisions: every
+        // branch ends with trial division up to √n, so the verdict is exact
+        Assessment {
+            verdict: is_prime_zeta(n),
+            certainty: Certainty::Proven,
+        }
+    }
 }
 
+/// Largest input accepted by `ZetaAlgorithm::try_is_prime`
+///
+/// Every branch of the zeta test ends with trial division up to √n, so above
+/// 10^12 (about 5 × 10^5 odd divisors below 10^6) the running time quickly
+/// becomes impractical.
+pub const ZETA_MAX_RELIABLE: u64 = 1_000_000_000_000;
+
 /// First 50 non-trivial zeros of zeta(s) on the critical line (imaginary parts)
 /// Under RH: zeta(1/2 + i*gamma) = 0
 /// These frequencies determine the oscillations in prime distribution
@@ -73,51 +114,23 @@ const ZETA_ZEROS: [f64; 50] = [
 
 /// Tests if a number is prime using zeta-based spectroscopic analysis
 pub fn is_prime_zeta<N: PrimInt + ToPrimitive + FromPrimitive + Unsigned>(n: N) -> bool {
-    let zero = N::zero();
-    let one = N::one();
-    let two = N::from_u64(2).unwrap();
-    let three = N::from_u64(3).unwrap();
-
     // Handle trivial cases
-    if n <= one { return false; }
-    if n == two { return true; }
-    if n % two == zero { return false; }
-    if n == three { return true; }
+    if let Some(result) = trivial_primality(n) {
+        return result;
+    }
 
     let n_u64 = n.to_u64().unwrap();
 
-    // For small numbers, trial division is more efficient
+    // trivial_primality settled n < 4 and even n, so what is left below 100
+    // is an odd n that only needs looking up
     if n_u64 < 100 {
-        return is_prime_trial_small(n);
+        return SMALL_PRIMES.binary_search(&n_u64).is_ok();
     }
 
     // Use zeta spectroscopic analysis
     zeta_spectroscopic_test(n)
 }
 
-/// Trial division for small numbers
-fn is_prime_trial_small<N: PrimInt + ToPrimitive + FromPrimitive>(n: N) -> bool {
-    let zero = N::zero();
-    let one = N::one();
-    let two = N::from_u64(2).unwrap();
-
-    if n <= one { return false; }
-    if n == two { return true; }
-    if n % two == zero { return false; }
-
-    let n_f64 = n.to_f64().unwrap();
-    let limit = N::from_u64(n_f64.sqrt() as u64 + 1).unwrap();
-
-    let mut i = N::from_u64(3).unwrap();
-    while i <= limit {
-        if n % i == zero {
-            return false;
-        }
-        i = i + two;
-    }
-    true
-}
-
 /// Compute the oscillatory signature at n using zeta zeros
 ///
 /// This is the key innovation: we compute the sum over zeta zeros that
@@ -135,9 +148,7 @@ fn zeta_oscillation(n: f64, num_zeros: usize) -> f64 {
 
     let zeros_to_use = num_zeros.min(ZETA_ZEROS.len());
 
-    for i in 0..zeros_to_use {
-        let gamma = ZETA_ZEROS[i];
-        
+    for &gamma in ZETA_ZEROS.iter().take(zeros_to_use) {
         // Under RH: rho = 1/2 + i*gamma
         // x^rho = x^(1/2) * exp(i*gamma*log(x))
         //       = sqrt(x) * (cos(gamma*log(x)) + i*sin(gamma*log(x)))
@@ -153,6 +164,156 @@ fn zeta_oscillation(n: f64, num_zeros: usize) -> f64 {
     oscillation / sqrt_n
 }
 
+/// Number of integer points sampled on each side of n by `oscillation_profile`
+pub const PROFILE_RADIUS: u64 = 10;
+
+/// Samples the zeta oscillation at the integers around n
+///
+/// Exposes the interference pattern that the spectroscopic test relies on,
+/// so its behavior can be inspected or plotted. The samples are taken at
+/// every integer in [n - PROFILE_RADIUS, n + PROFILE_RADIUS], clamped below at 1
+/// where the oscillation is undefined.
+///
+/// # Arguments
+///
+/// * `n` - Center of the sampled window
+/// * `num_zeros` - Number of zeta zeros summed, capped at the 50 known to the crate
+///
+/// # Returns
+///
+/// The oscillation values in ascending order of the sample point; the vector
+/// holds 2 × PROFILE_RADIUS + 1 values unless the window was clamped
+pub fn oscillation_profile(n: u64, num_zeros: usize) -> Vec<f64> {
+    let low = n.saturating_sub(PROFILE_RADIUS).max(1);
+    let high = n.saturating_add(PROFILE_RADIUS);
+
+    (low..=high)
+        .map(|x| zeta_oscillation(x as f64, num_zeros))
+        .collect()
+}
+
+/// Inputs over which `benchmark_accuracy_tradeoff` measures the zeta test
+pub const TRADEOFF_RANGE: RangeInclusive<u64> = 10_000..=20_000;
+
+/// Measures how the zeta test's accuracy and runtime depend on the zero count
+///
+/// The test always confirms its verdict by trial division, so its answers
+/// are correct whatever the number of zeros; what the zeros buy is the
+/// prime score that decides how that verification is spent. For each zero
+/// count from 5 to the full table in steps of 5, this function scores every
+/// input of `TRADEOFF_RANGE` that survives the screen by the primes below 100,
+/// and counts a score above the high threshold on a prime, or below the low
+/// threshold on a composite, as a correct call. Scores in between are
+/// undecided and count against the accuracy.
+///
+/// # Returns
+///
+/// One `(num_zeros, accuracy, runtime)` row per zero count, in increasing
+/// order, where accuracy is the fraction of correct calls and runtime is the
+/// time the complete test took over the range
+pub fn benchmark_accuracy_tradeoff() -> Vec<(usize, f64, Duration)> {
+    let screened: Vec<u64> = TRADEOFF_RANGE
+        .filter(|&n| SMALL_PRIMES.iter().all(|&p| !n.is_multiple_of(p)))
+        .collect();
+
+    (5..=ZETA_ZEROS.len())
+        .step_by(5)
+        .map(|num_zeros| {
+            let correct = screened
+                .iter()
+                .filter(|&&n| {
+                    let score = prime_probability_score(n as f64, num_zeros);
+                    match is_prime_miller_rabin(n, 0) {
+                        true => score > HIGH_SCORE_THRESHOLD,
+                        false => score < LOW_SCORE_THRESHOLD,
+                    }
+                })
+                .count();
+
+            let start = Instant::now();
+            for n in TRADEOFF_RANGE {
+                std::hint::black_box(zeta_spectroscopic_test_with(n, num_zeros));
+            }
+            let runtime = start.elapsed();
+
+            (num_zeros, correct as f64 / screened.len() as f64, runtime)
+        })
+        .collect()
+}
+
+/// Zero counts of `optimal_zeros_for`, as (exclusive upper bound of n, zeros)
+const ZERO_CALIBRATION: [(u64, usize); 2] = [(5_000, 1), (u64::MAX, 4)];
+
+/// Number of zeta zeros the spectroscopic test sums for n
+///
+/// Calibrated with the accuracy measure of `benchmark_accuracy_tradeoff`,
+/// the fraction of inputs surviving the small-prime screen whose score lands
+/// on the correct side of the thresholds, over windows of up to 10,000
+/// inputs starting between 10^2 and 10^9, and every zero count from 1 to the full table:
+///
+/// * below 5,000 no zero count moves any score out of the undecided band,
+///   so a single zero is summed
+/// * from there to 10^9 the accuracy stops improving after 4 zeros at the
+///   latest (near 10^4; 3 zeros suffice near 10^5 and 1 or 2 from 10^6)
+///
+/// The selector keeps 4 zeros above 5,000 rather than following the smaller
+/// counts of the larger windows, so it never sums fewer zeros for a larger n.
+/// More zeros do not change any verdict, which trial division always
+/// confirms, only the cost of reaching it.
+///
+/// # Returns
+///
+/// A zero count between 1 and the number of embedded zeros
+pub fn optimal_zeros_for(n: u64) -> usize {
+    ZERO_CALIBRATION
+        .iter()
+        .find(|&&(bound, _)| n < bound)
+        .map_or(ZERO_CALIBRATION[ZERO_CALIBRATION.len() - 1].1, |&(_, zeros)| zeros)
+}
+
+/// The explicit-formula estimate of ψ(n) - ψ(n - 1) next to the true value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PsiAnalysis {
+    /// The analyzed integer
+    pub n: u64,
+    /// Number of zeta zeros summed by the estimate
+    pub num_zeros: usize,
+    /// Jump of ψ at n estimated from the zeta zeros, as used by the test
+    pub estimate: f64,
+    /// The true jump, the von Mangoldt function Λ(n)
+    pub actual: f64,
+    /// `estimate - actual`
+    pub error: f64,
+}
+
+/// Compares the zeta module's estimate of the ψ jump at n with Λ(n)
+///
+/// The spectroscopic test scores n partly by how closely the explicit
+/// formula, truncated to `num_zeros` zeros, reproduces the jump of the
+/// Chebyshev function ψ at n. That jump is exactly the von Mangoldt function:
+/// ln p when n is a power of the prime p, and 0 otherwise. This function
+/// reports both, so the quality of the approximation can be seen directly.
+///
+/// # Arguments
+///
+/// * `n` - The integer to analyze, at least 1
+/// * `num_zeros` - Number of zeta zeros summed, capped at the 50 known to the crate
+///
+/// # Returns
+///
+/// A `PsiAnalysis` with the estimate, the true value and their difference
+pub fn psi_analysis(n: u64, num_zeros: usize) -> PsiAnalysis {
+    let estimate = psi_jump_estimate(n as f64, num_zeros);
+    let actual = von_mangoldt(n);
+    PsiAnalysis {
+        n,
+        num_zeros,
+        estimate,
+        actual,
+        error: estimate - actual,
+    }
+}
+
 /// Compute Chebyshev psi function jump at n
 ///
 /// psi(n) - psi(n-1) = log(p) if n = p^k for prime p, else 0
@@ -217,13 +378,10 @@ fn prime_probability_score(n: f64, num_zeros: usize) -> f64 {
     let spectral_power = compute_spectral_signature(n, num_zeros);
     
     // Combined score
-    let score = 
-        jump_ratio * 2.0 +
+    jump_ratio * 2.0 +
         extremum_score +
         coherence * 1.5 +
-        spectral_power * 1.0;
-    
-    score
+        spectral_power * 1.0
 }
 
 /// Compute spectral signature: how much "prime energy" at frequency n
@@ -235,8 +393,7 @@ fn compute_spectral_signature(n: f64, num_zeros: usize) -> f64 {
     let mut spectral_sum = 0.0;
     
     // Weight lower zeros more heavily (they contribute more to small x)
-    for i in 0..num_zeros.min(ZETA_ZEROS.len()) {
-        let gamma = ZETA_ZEROS[i];
+    for (i, &gamma) in ZETA_ZEROS.iter().enumerate().take(num_zeros.min(ZETA_ZEROS.len())) {
         let weight = 1.0 / (1.0 + (i as f64) * 0.1);
         
         // Compute resonance at this frequency
@@ -249,54 +406,54 @@ fn compute_spectral_signature(n: f64, num_zeros: usize) -> f64 {
     spectral_sum / num_zeros.min(ZETA_ZEROS.len()) as f64
 }
 
+/// Primes below 100, screened out before any spectral analysis
+const SMALL_PRIMES: [u64; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47,
+    53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Scores above this are read as a strong prime signature
+///
+/// Thresholds determined empirically from zeta theory
+const HIGH_SCORE_THRESHOLD: f64 = 5.5;
+
+/// Scores below this are read as a composite signature
+const LOW_SCORE_THRESHOLD: f64 = 3.0;
+
 /// Main zeta spectroscopic primality test
 ///
 /// This is where we truly use RH-based analysis rather than just
 /// optimizing trial division bounds.
 fn zeta_spectroscopic_test<N: PrimInt + ToPrimitive + FromPrimitive>(n: N) -> bool {
+    zeta_spectroscopic_test_with(n, optimal_zeros_for(n.to_u64().unwrap()))
+}
+
+/// Spectroscopic test summing a given number of zeta zeros
+fn zeta_spectroscopic_test_with<N: PrimInt + ToPrimitive + FromPrimitive>(n: N, num_zeros: usize) -> bool {
     let n_u64 = n.to_u64().unwrap();
     let n_f64 = n_u64 as f64;
     let zero = N::zero();
     let two = N::from_u64(2).unwrap();
 
     // Quick divisibility by small primes
-    let small_primes = [
-        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47,
-        53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
-    ];
-
-    for &p in &small_primes {
+    for &p in &SMALL_PRIMES {
         let p_n = N::from_u64(p).unwrap();
         if n == p_n { return true; }
         if n % p_n == zero { return false; }
     }
 
-    // Determine number of zeros to use based on n
-    // More zeros give better accuracy but take longer
-    let num_zeros = if n_f64 < 1000.0 {
-        20
-    } else if n_f64 < 10000.0 {
-        30
-    } else {
-        40
-    };
-
     // Compute spectroscopic prime probability
     let prime_score = prime_probability_score(n_f64, num_zeros);
     
-    // Thresholds determined empirically from zeta theory
     // High score: very likely prime, do minimal verification
     // Low score: likely composite, do quick check
     // Medium score: uncertain, do full trial division
-    
-    let high_threshold = 5.5;
-    let low_threshold = 3.0;
 
-    if prime_score > high_threshold {
+    if prime_score > HIGH_SCORE_THRESHOLD {
         // Strong prime signature from zeta analysis
         // Do minimal verification - just check up to small bound
         let quick_limit = (n_f64.sqrt() as u64).min(1000);
-        let verify_limit = N::from_u64(quick_limit).unwrap();
+        let verify_limit = saturating_from_u64(quick_limit);
         
         let mut d = N::from_u64(101).unwrap();
         while d <= verify_limit {
@@ -306,19 +463,19 @@ fn zeta_spectroscopic_test<N: PrimInt + ToPrimitive + FromPrimitive>(n: N) -> bo
         
         // If no small divisors and strong zeta signature, likely prime
         // Do extended check up to sqrt(n)
-        let full_limit = N::from_u64(n_f64.sqrt() as u64 + 1).unwrap();
+        let full_limit = trial_division_limit(n);
         while d <= full_limit {
             if n % d == zero { return false; }
             d = d + two;
         }
         
-        return true;
-        
-    } else if prime_score < low_threshold {
+        true
+
+    } else if prime_score < LOW_SCORE_THRESHOLD {
         // Weak prime signature - likely composite
         // Quick verification up to small bound
         let quick_limit = (n_f64.sqrt() as u64).min(5000);
-        let verify_limit = N::from_u64(quick_limit).unwrap();
+        let verify_limit = saturating_from_u64(quick_limit);
         
         let mut d = N::from_u64(101).unwrap();
         while d <= verify_limit {
@@ -327,40 +484,26 @@ fn zeta_spectroscopic_test<N: PrimInt + ToPrimitive + FromPrimitive>(n: N) -> bo
         }
         
         // Still no divisor found, must do full check despite low score
-        let full_limit = N::from_u64(n_f64.sqrt() as u64 + 1).unwrap();
+        let full_limit = trial_division_limit(n);
         while d <= full_limit {
             if n % d == zero { return false; }
             d = d + two;
         }
         
-        return true;
-        
+        true
+
     } else {
         // Medium score - uncertain, do standard trial division
-        let sqrt_n = n_f64.sqrt();
-        let limit = N::from_u64(sqrt_n as u64 + 1).unwrap();
+        let limit = trial_division_limit(n);
         
         let mut d = N::from_u64(101).unwrap();
         while d <= limit {
             if n % d == zero { return false; }
-            
-            // Use oscillation-guided skipping
-            let d_f64 = d.to_u64().unwrap() as f64;
-            if d_f64 > 1000.0 && d_f64 as u64 % 100 == 0 {
-                // Check local prime density using zeta oscillations
-                let local_osc = zeta_oscillation(d_f64, 10);
-                
-                // If oscillation suggests low prime density, skip ahead
-                if local_osc.abs() < 0.01 {
-                    let skip = ((sqrt_n / 50.0) as u64).max(10);
-                    d = N::from_u64(d.to_u64().unwrap() + skip).unwrap();
-                    continue;
-                }
-            }
-            
+            // Every odd candidate is checked: skipping divisors on the strength
+            // of the oscillation alone could step over a real factor
             d = d + two;
         }
-        
-        return true;
+
+        true
     }
 }
@@ -1,44 +1,167 @@
+//! Erato - A library for primality testing algorithms
+//!
+//! This library provides multiple implementations of primality testing algorithms
+//! with a unified trait-based interface for easy comparison and benchmarking.
+//!
+//! # Features
+//!
+//! - **Multiple algorithms**: Sieve of Eratosthenes and Miller-Rabin
+//! - **Unified interface**: Implement `PrimalityTest` trait for custom algorithms
+//! - **Registry system**: Easily manage and compare all algorithms
+//! - **Well-tested**: Comprehensive test coverage
+
 use wasm_bindgen::prelude::*;
 
-/// Erato - A library for primality testing algorithms
+pub mod algorithms;
+
+pub use algorithms::sieve::{
+    is_prime_cancellable, is_prime_const, is_prime_sieve, is_prime_sieve_instrumented, is_prime_u32_noalloc,
+    primes_in_range, primes_in_ranges, primes_up_to, smallest_factor_sieve, PrecomputedSieve, SegmentedSieve,
+    SieveAlgorithm, SieveError, SievePolicy,
+};
+#[cfg(feature = "parallel")]
+pub use algorithms::sieve::primes_up_to_parallel;
+pub use algorithms::miller_rabin::{
+    base_strength, is_prime_any, is_prime_miller_rabin, is_prime_miller_rabin_extra, is_strong_probable_prime,
+    MillerRabinAlgorithm, DETERMINISTIC_WITNESSES, SORENSON_WEBSTER_BASES,
+};
+pub use algorithms::bpsw::{is_lucas_prp_with_params, is_strong_lucas_probable_prime, probable_prime_report, PrpReport};
+pub use algorithms::zeta::{
+    benchmark_accuracy_tradeoff, is_prime_zeta, optimal_zeros_for, oscillation_profile, psi_analysis, PsiAnalysis,
+    ZetaAlgorithm, PROFILE_RADIUS, TRADEOFF_RANGE, ZETA_MAX_RELIABLE,
+};
+pub use algorithms::arith::{
+    bit_length, checked_affine, decimal_digits, integer_sqrt, is_perfect_power, jacobi, mod_sqrt, ModArith, Montgomery,
+};
+pub use algorithms::chain::{Chain, TrialDivisionScreen};
+pub use algorithms::fuzz::{fuzz_algorithms, Divergence, FuzzReport};
+pub use algorithms::parse::{is_prime_bytes_be, is_prime_str, parse_number, ParseError};
+pub use algorithms::suite::{
+    benchmark_buckets, run_benchmark_suite, score_algorithms, AlgorithmScore, BenchmarkSuiteResult, BucketTiming,
+};
+pub use algorithms::generate::{GenError, PrimeGenerator, DEFAULT_MAX_ATTEMPTS};
+pub use algorithms::factor::{
+    big_omega, classify_number, classify_prime_power, count_divisors, distinct_prime_factors, divisors, factor_tree,
+    factorize, factorize_flat, factorize_powers, factorize_struct, has_small_factor, is_carmichael, liouville, mobius,
+    number_class, pollard_rho_bounded, prime_signature, radical, sum_divisors, sum_of_two_squares, von_mangoldt,
+    FactorError, FactorNode, Factorization, NumberClass, NumberSummary, PrimePowerClass,
+};
+pub use algorithms::counting::{
+    liouville_sum, local_prime_density, logarithmic_integral, mertens, mobius_sieve, pi_inverse,
+    prime_count_with_bounds, prime_pi, prime_pi_legendre, prime_pi_riemann, riemann_r, verify_pi_against_known,
+    PRIME_PI_AT_POWERS_OF_TEN,
+};
+pub use algorithms::primes::{
+    next_prime, primes_until, BoundedPrimeIterator, PrimeIterator, PrimesInRange, RangePrimes, WindowedPrimes, Wheel,
+    WheelCandidates, LARGEST_U64_PRIME,
+};
+pub use algorithms::special_forms::{
+    best_quadratic_prime_run, classify_special_form, cunningham_chain_first_kind, is_mersenne_number,
+    is_prime_special_form, is_proth_prime, is_safe_prime, is_sophie_germain_prime, mersenne_prime_exponents, twin_prime_near,
+    SpecialForm, CUNNINGHAM_SEARCH_WINDOW, MAX_MERSENNE_EXPONENT, TWIN_PRIME_SEARCH_RADIUS,
+};
+pub use algorithms::rng::{RandomSource, SplitMix64};
+pub use algorithms::vectors::{test_vectors, TestVectors};
+pub use algorithms::{
+    quick_composite_check, trivial_primality, Algorithm, AlgorithmId, AlgorithmInfo, Assessment, Certainty,
+    ComparisonConfig, ComparisonReport, ComparisonRow, Complexity, FnAlgorithm, PrimalityError, PrimalityRegistry,
+    PrimalityTest, UnknownAlgorithmError,
+};
+
+#[wasm_bindgen]
+pub fn is_prime(n: u64) -> bool {
+    is_prime_zeta(n)
+}
+
+/// Prime factors of n with multiplicity, as a typed array for JS
 ///
-/// This library provides multiple implementations of primality testing algorithms
-/// with a unified trait-based interface for easy comparison and benchmarking.
+/// The output of `factorize`: 360 gives [2, 2, 2, 3, 3, 5], and 0 and 1
+/// give an empty array.
+#[wasm_bindgen]
+pub fn factorize_js(n: u64) -> Vec<u64> {
+    factorize(n)
+}
+
+/// Most points sampled on the prime-counting chart
+const CHART_POINTS: u64 = 1000;
+
+/// x values of the prime-counting chart for [2, max_n]
 ///
-/// # Features
+/// Every integer of the range when it holds at most `CHART_POINTS` of them,
+/// otherwise `CHART_POINTS` values spread as evenly as integers allow, from 2
+/// to max_n. The number of points grows with max_n up to the cap and then
+/// stays there, so close values of max_n give charts of the same resolution.
+fn chart_xs(max_n: u64) -> Vec<u64> {
+    if max_n < 2 {
+        return Vec::new();
+    }
+    let span = (max_n - 2) as u128;
+    let points = (max_n - 1).min(CHART_POINTS) as u128;
+    (0..points)
+        .map(|i| 2 + (span * i / (points - 1).max(1)) as u64)
+        .collect()
+}
+
+/// Sample points of the prime-counting chart: x values and pi(x) at each
 ///
-/// - **Multiple algorithms**: Sieve of Eratosthenes and Miller-Rabin
-/// - **Unified interface**: Implement `PrimalityTest` trait for custom algorithms
-/// - **Registry system**: Easily manage and compare all algorithms
-/// - **Well-tested**: Comprehensive test coverage
+/// The x values are those of `prime_count_data`, from `chart_xs`. The counts
+/// come from a single sieve up to max_n, so each y equals `prime_pi(x)`.
+pub(crate) fn prime_count_points(max_n: u64) -> (Vec<u64>, Vec<u64>) {
+    let xs = chart_xs(max_n);
 
-pub mod algorithms;
+    let primes = primes_up_to(max_n);
+    let ys = xs
+        .iter()
+        .map(|&x| primes.partition_point(|&p| p <= x) as u64)
+        .collect();
 
-pub use algorithms::sieve::{is_prime_sieve, SieveAlgorithm};
-pub use algorithms::miller_rabin::{is_prime_miller_rabin, MillerRabinAlgorithm};
-pub use algorithms::zeta::{is_prime_zeta, ZetaAlgorithm};
-pub use algorithms::{PrimalityTest, PrimalityRegistry};
+    (xs, ys)
+}
 
+/// Points of the prime-counting chart, as two typed arrays for JS
 #[wasm_bindgen]
-pub fn is_prime(n: u64) -> bool {
-    is_prime_zeta(n)
+pub struct PrimeCountSeries {
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+}
+
+#[wasm_bindgen]
+impl PrimeCountSeries {
+    /// x coordinates of the chart
+    #[wasm_bindgen(getter)]
+    pub fn xs(&self) -> Vec<u64> {
+        self.xs.clone()
+    }
+
+    /// pi(x) at each of the x coordinates
+    #[wasm_bindgen(getter)]
+    pub fn ys(&self) -> Vec<u64> {
+        self.ys.clone()
+    }
+}
+
+/// Prime-counting chart points up to max_n, sieved once for both coordinates
+#[wasm_bindgen]
+pub fn prime_count_series(max_n: u64) -> PrimeCountSeries {
+    let (xs, ys) = prime_count_points(max_n);
+    PrimeCountSeries { xs, ys }
 }
 
 #[wasm_bindgen]
 pub fn prime_count_data(max_n: u64) -> Vec<u64> {
     let mut data = Vec::new();
     let mut count = 0u64;
-    
+    let mut xs = chart_xs(max_n).into_iter().peekable();
+
     for n in 2..=max_n {
         if is_prime_zeta(n) {
             count += 1;
         }
-        let step = if max_n > 10000 { 100 } else if max_n > 1000 { 10 } else { 1 };
-        if n % step == 0 || n == max_n {
+        if xs.next_if_eq(&n).is_some() {
             data.push(n);
             data.push(count);
         }
     }
-    
+
     data
 }